@@ -0,0 +1,288 @@
+use anchor_lang::{
+    prelude::*,
+    solana_program::program::{invoke, invoke_signed},
+};
+use anchor_spl::{
+    associated_token,
+    token_2022::{
+        self, spl_token_2022::instruction::AuthorityType, InitializeMint2, MintTo, SetAuthority,
+    },
+};
+use spl_token_2022::{
+    extension::{group_member_pointer, metadata_pointer, ExtensionType},
+    instruction::initialize_non_transferable_mint,
+    state::Mint,
+};
+use spl_token_group_interface::instruction as group_instruction;
+use spl_token_metadata_interface::{
+    instruction as metadata_instruction,
+    state::{Field, TokenMetadata},
+};
+
+use crate::{states::Profile, utils::create_pda_account};
+
+pub const NAME: &str = "DeezJobs Reputation";
+pub const SYMBOL: &str = "DEEZREP";
+
+// Metadata keys mirroring the Profile counters.
+pub const COMPLETED_DEALS: &str = "completed_deals";
+pub const DELIVERIES: &str = "deliveries";
+pub const ON_TIME_DELIVERIES: &str = "on_time_deliveries";
+pub const DISPUTES_WON: &str = "disputes_won";
+pub const DISPUTES_LOST: &str = "disputes_lost";
+pub const RATING: &str = "rating";
+
+pub const KEYS: [&str; 6] = [
+    COMPLETED_DEALS,
+    DELIVERIES,
+    ON_TIME_DELIVERIES,
+    DISPUTES_WON,
+    DISPUTES_LOST,
+    RATING,
+];
+
+/// Metadata value of a Profile counter, the rating is the average with 2 decimal places.
+fn value(profile: &Profile, key: &str) -> String {
+    match key {
+        COMPLETED_DEALS => profile.completed_deals.to_string(),
+        DELIVERIES => profile.deliveries.to_string(),
+        ON_TIME_DELIVERIES => profile.on_time_deliveries.to_string(),
+        DISPUTES_WON => profile.disputes_won.to_string(),
+        DISPUTES_LOST => profile.disputes_lost.to_string(),
+        RATING => match profile.rating_count {
+            0 => String::from("0.00"),
+            count => {
+                let average = profile.rating_total * 100 / u64::from(count);
+                format!("{}.{:02}", average / 100, average % 100)
+            }
+        },
+        _ => String::new(),
+    }
+}
+
+/// Program accounts shared by every credential. The Config PDA is the mint and update authority,
+/// so a credential is genuine if its metadata's update authority is the Config, and it is a member
+/// of the collection created by `create_collection`.
+pub struct CredentialAuthority<'info> {
+    pub config: AccountInfo<'info>,
+    pub config_bump: u8,
+    pub collection: AccountInfo<'info>,
+    pub payer: AccountInfo<'info>,
+    pub token_program: AccountInfo<'info>,
+    pub associated_token_program: AccountInfo<'info>,
+    pub system_program: AccountInfo<'info>,
+}
+
+/// Non-transferable Token-2022 NFT of a wallet, its mint is the PDA [b"credential", holder].
+/// Instructions pass the mint and the holder's associated token account unchecked, as neither
+/// exists before `sync` mints the credential on the holder's first completed deal.
+pub struct Credential<'info> {
+    pub holder: AccountInfo<'info>,
+    pub mint: AccountInfo<'info>,
+    pub mint_bump: u8,
+    pub token_account: AccountInfo<'info>,
+}
+
+impl<'info> Credential<'info> {
+    /// Mints the credential once the holder completed a deal, afterwards only `keys` are refreshed.
+    pub fn sync(
+        &self,
+        authority: &CredentialAuthority<'info>,
+        profile: &Profile,
+        keys: &[&str],
+    ) -> Result<()> {
+        if !self.mint.data_is_empty() {
+            return self.update(authority, profile, keys);
+        }
+
+        if profile.completed_deals == 0 {
+            return Ok(());
+        }
+
+        self.mint(authority, profile)
+    }
+
+    fn mint(&self, authority: &CredentialAuthority<'info>, profile: &Profile) -> Result<()> {
+        let holder_key = self.holder.key();
+        let mint_key = self.mint.key();
+        let config_key = authority.config.key();
+        let mint_bump = [self.mint_bump];
+        let config_bump = [authority.config_bump];
+        let mint_seeds: &[&[u8]] = &[b"credential", holder_key.as_ref(), &mint_bump];
+        let config_seeds: &[&[u8]] = &[b"config", &config_bump];
+
+        let space = ExtensionType::try_calculate_account_len::<Mint>(&[
+            ExtensionType::NonTransferable,
+            ExtensionType::MetadataPointer,
+            ExtensionType::GroupMemberPointer,
+        ])?;
+
+        // The group membership and the metadata are stored on the mint, rent is paid upfront
+        // for the largest counters so later updates never need a top up.
+        let member_space = ExtensionType::try_calculate_account_len::<Mint>(&[
+            ExtensionType::NonTransferable,
+            ExtensionType::MetadataPointer,
+            ExtensionType::GroupMemberPointer,
+            ExtensionType::TokenGroupMember,
+        ])?;
+
+        let largest = TokenMetadata {
+            mint: mint_key,
+            name: NAME.to_string(),
+            symbol: SYMBOL.to_string(),
+            additional_metadata: KEYS
+                .iter()
+                .map(|key| (key.to_string(), u64::MAX.to_string()))
+                .collect(),
+            ..Default::default()
+        };
+
+        let lamports = Rent::get()?.minimum_balance(member_space + largest.tlv_size_of()?);
+
+        create_pda_account(
+            &authority.payer,
+            &self.mint,
+            &authority.system_program,
+            mint_seeds,
+            space,
+            lamports,
+            &token_2022::ID,
+        )?;
+
+        invoke(
+            &initialize_non_transferable_mint(&token_2022::ID, &mint_key)?,
+            std::slice::from_ref(&self.mint),
+        )?;
+
+        invoke(
+            &metadata_pointer::instruction::initialize(
+                &token_2022::ID,
+                &mint_key,
+                None,
+                Some(mint_key),
+            )?,
+            std::slice::from_ref(&self.mint),
+        )?;
+
+        invoke(
+            &group_member_pointer::instruction::initialize(
+                &token_2022::ID,
+                &mint_key,
+                None,
+                Some(mint_key),
+            )?,
+            std::slice::from_ref(&self.mint),
+        )?;
+
+        token_2022::initialize_mint2(
+            CpiContext::new(
+                authority.token_program.clone(),
+                InitializeMint2 {
+                    mint: self.mint.clone(),
+                },
+            ),
+            0,
+            &config_key,
+            None,
+        )?;
+
+        invoke_signed(
+            &metadata_instruction::initialize(
+                &token_2022::ID,
+                &mint_key,
+                &config_key,
+                &mint_key,
+                &config_key,
+                NAME.to_string(),
+                SYMBOL.to_string(),
+                String::new(),
+            ),
+            &[self.mint.clone(), authority.config.clone()],
+            &[config_seeds],
+        )?;
+
+        invoke_signed(
+            &group_instruction::initialize_member(
+                &token_2022::ID,
+                &mint_key,
+                &mint_key,
+                &config_key,
+                &authority.collection.key(),
+                &config_key,
+            ),
+            &[
+                self.mint.clone(),
+                authority.config.clone(),
+                authority.collection.clone(),
+            ],
+            &[config_seeds],
+        )?;
+
+        self.update(authority, profile, &KEYS)?;
+
+        associated_token::create(CpiContext::new(
+            authority.associated_token_program.clone(),
+            associated_token::Create {
+                payer: authority.payer.clone(),
+                associated_token: self.token_account.clone(),
+                authority: self.holder.clone(),
+                mint: self.mint.clone(),
+                system_program: authority.system_program.clone(),
+                token_program: authority.token_program.clone(),
+            },
+        ))?;
+
+        token_2022::mint_to(
+            CpiContext::new_with_signer(
+                authority.token_program.clone(),
+                MintTo {
+                    mint: self.mint.clone(),
+                    to: self.token_account.clone(),
+                    authority: authority.config.clone(),
+                },
+                &[config_seeds],
+            ),
+            1,
+        )?;
+
+        // One of a kind, the metadata stays updatable.
+        token_2022::set_authority(
+            CpiContext::new_with_signer(
+                authority.token_program.clone(),
+                SetAuthority {
+                    current_authority: authority.config.clone(),
+                    account_or_mint: self.mint.clone(),
+                },
+                &[config_seeds],
+            ),
+            AuthorityType::MintTokens,
+            None,
+        )
+    }
+
+    fn update(
+        &self,
+        authority: &CredentialAuthority<'info>,
+        profile: &Profile,
+        keys: &[&str],
+    ) -> Result<()> {
+        let config_bump = [authority.config_bump];
+        let config_seeds: &[&[u8]] = &[b"config", &config_bump];
+
+        for key in keys {
+            invoke_signed(
+                &metadata_instruction::update_field(
+                    &token_2022::ID,
+                    &self.mint.key(),
+                    &authority.config.key(),
+                    Field::Key(key.to_string()),
+                    value(profile, key),
+                ),
+                &[self.mint.clone(), authority.config.clone()],
+                &[config_seeds],
+            )?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,170 @@
+use anchor_lang::prelude::*;
+
+use crate::states::GigState;
+
+#[event]
+pub struct DealRejected {
+    pub deal: Pubkey,
+    pub gig: Pubkey,
+    pub client: Pubkey,
+    pub freelancer: Pubkey,
+    /// Reason of the rejection.
+    /// * 0 - Other
+    /// * 1 - Offer is too low
+    /// * 2 - Deadline is too short
+    /// * 3 - Freelancer is unavailable
+    /// * 4 - Request is out of the gig's scope
+    pub reason: u8,
+}
+
+#[event]
+pub struct GigCreated {
+    pub gig: Pubkey,
+    pub owner: Pubkey,
+    /// None if the gig is paid in SOL.
+    pub mint: Option<Pubkey>,
+    pub asking: u64,
+    pub state: GigState,
+}
+
+#[event]
+pub struct GigUpdated {
+    pub gig: Pubkey,
+    pub asking: u64,
+    pub min_completion_time: i64,
+}
+
+#[event]
+pub struct GigStateChanged {
+    pub gig: Pubkey,
+    pub state: GigState,
+}
+
+#[event]
+pub struct GigClosed {
+    pub gig: Pubkey,
+    pub owner: Pubkey,
+}
+
+#[event]
+pub struct DealCreated {
+    pub deal: Pubkey,
+    pub gig: Pubkey,
+    pub client: Pubkey,
+    pub freelancer: Pubkey,
+    pub offer: u64,
+    pub deadline: i64,
+    /// Escrowed on top of the offer.
+    pub client_fee: u64,
+    pub referrer: Option<Pubkey>,
+}
+
+#[event]
+pub struct CounterOffered {
+    pub deal: Pubkey,
+    pub offer: u64,
+    pub deadline: i64,
+}
+
+/// Terms of the deal changed, either from an accepted counter offer or a revised offer.
+#[event]
+pub struct OfferUpdated {
+    pub deal: Pubkey,
+    pub offer: u64,
+    pub deadline: i64,
+}
+
+#[event]
+pub struct DealAccepted {
+    pub deal: Pubkey,
+    pub gig: Pubkey,
+    pub freelancer: Pubkey,
+}
+
+/// Deal was withdrawn before the freelancer accepted it, the escrow is refunded.
+#[event]
+pub struct DealClosed {
+    pub deal: Pubkey,
+    pub gig: Pubkey,
+    pub client: Pubkey,
+}
+
+/// Freelancer missed the deadline, the escrow is refunded.
+#[event]
+pub struct DealExpired {
+    pub deal: Pubkey,
+    pub gig: Pubkey,
+    pub client: Pubkey,
+}
+
+#[event]
+pub struct DeliverySubmitted {
+    pub deal: Pubkey,
+    pub delivery: [u8; 32],
+}
+
+/// A milestone was paid out, the deal stays open until the last one.
+#[event]
+pub struct MilestoneReleased {
+    pub deal: Pubkey,
+    pub amount: u64,
+    pub freelancer_pay: u64,
+    pub referral_pay: u64,
+    pub treasury_pay: u64,
+    /// Part of `treasury_pay` charged to the client.
+    pub client_fee: u64,
+    /// Withheld by Token-2022 mints with a transfer fee.
+    pub withheld_fee: u64,
+}
+
+/// Fee breakdown of the final payout, earlier milestones are in `MilestoneReleased`.
+#[event]
+pub struct DealCompleted {
+    pub deal: Pubkey,
+    pub gig: Pubkey,
+    pub client: Pubkey,
+    pub freelancer: Pubkey,
+    pub freelancer_pay: u64,
+    pub referral_pay: u64,
+    pub treasury_pay: u64,
+    /// Part of `treasury_pay` charged to the client.
+    pub client_fee: u64,
+    /// Withheld by Token-2022 mints with a transfer fee.
+    pub withheld_fee: u64,
+}
+
+#[event]
+pub struct DisputeRaised {
+    pub dispute: Pubkey,
+    pub deal: Pubkey,
+    pub opened_by: Pubkey,
+    pub reason: u8,
+}
+
+#[event]
+pub struct DisputeSettled {
+    pub dispute: Pubkey,
+    pub deal: Pubkey,
+    /// Percentage with 2 decimal places (0 to 10000).
+    pub client_share: u16,
+    pub client_pay: u64,
+    pub freelancer_pay: u64,
+    pub treasury_pay: u64,
+    /// Withheld by Token-2022 mints with a transfer fee.
+    pub withheld_fee: u64,
+}
+
+#[event]
+pub struct ReferrerRegistered {
+    pub referrer: Pubkey,
+    pub owner: Pubkey,
+}
+
+#[event]
+pub struct ReviewLeft {
+    pub review: Pubkey,
+    pub deal: Pubkey,
+    pub author: Pubkey,
+    pub subject: Pubkey,
+    pub score: u8,
+}
@@ -0,0 +1,23 @@
+use crate::{states::Config, CustomError};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct AcceptAuthority<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = config.pending_authority == Some(new_authority.key()) @ CustomError::Unauthorized,
+    )]
+    pub config: Account<'info, Config>,
+
+    pub new_authority: Signer<'info>,
+}
+
+pub fn accept_authority_handler(ctx: Context<AcceptAuthority>) -> Result<()> {
+    let config = &mut ctx.accounts.config;
+    config.authority = ctx.accounts.new_authority.key();
+    config.pending_authority = None;
+
+    Ok(())
+}
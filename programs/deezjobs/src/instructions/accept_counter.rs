@@ -0,0 +1,203 @@
+use anchor_lang::{prelude::*, system_program};
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface, TransferChecked};
+
+use crate::{
+    events::OfferUpdated,
+    states::{Deal, DealState, Gig, MintConfig},
+    utils::{amount_with_fee, escrowed_lamports, transfer_lamports},
+    CustomError,
+};
+
+#[derive(AnchorDeserialize, AnchorSerialize)]
+pub struct AcceptCounterParams {
+    /// Terms the client agrees to, the freelancer may have replaced the counter offer in the meantime.
+    pub expected_offer: u64,
+    pub expected_deadline: i64,
+}
+
+/// Shared by accept_counter and revise_offer, both replace the terms of an open deal.
+#[derive(Accounts)]
+pub struct UpdateOffer<'info> {
+    #[account(
+        mut,
+        constraint = deal.client.key() == client.key() @ CustomError::Unauthorized,
+    )]
+    pub deal: Box<Account<'info, Deal>>,
+
+    #[account(
+        constraint = gig.key() == deal.gig.key() @ CustomError::GigMismatch,
+        constraint = gig.mint == mint.as_ref().map(|mint| mint.key()) @ CustomError::MintMismatch,
+    )]
+    pub gig: Box<Account<'info, Gig>>,
+
+    #[account(
+        seeds = [
+            b"mint",
+            mint_config.mint.as_ref(),
+        ],
+        bump = mint_config.bump,
+        constraint = mint_config.mint == gig.payment_mint() @ CustomError::MintNotListed,
+    )]
+    pub mint_config: Box<Account<'info, MintConfig>>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = deal,
+        associated_token::token_program = token_program,
+    )]
+    pub escrow: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = client,
+        associated_token::token_program = token_program,
+    )]
+    pub owner_wallet: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    // Omitted if the gig is paid in SOL.
+    pub mint: Option<Box<InterfaceAccount<'info, Mint>>>,
+
+    #[account(mut)]
+    pub client: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+pub fn accept_counter_handler(
+    ctx: Context<UpdateOffer>,
+    params: AcceptCounterParams,
+) -> Result<()> {
+    let deal = &ctx.accounts.deal;
+
+    let offer = deal
+        .counter_offer
+        .ok_or(error!(CustomError::NoCounterOffer))?;
+    let deadline = deal.counter_deadline;
+
+    if offer != params.expected_offer || deadline != params.expected_deadline {
+        return Err(error!(CustomError::TermsMismatch));
+    }
+
+    apply_offer(ctx, offer, deadline)
+}
+
+/// Replaces the terms of the deal, then tops up or partially refunds the escrow to match.
+pub fn apply_offer(ctx: Context<UpdateOffer>, offer: u64, deadline: i64) -> Result<()> {
+    let deal = &mut ctx.accounts.deal;
+    let gig = &ctx.accounts.gig;
+    let client = &ctx.accounts.client;
+    let escrow = &ctx.accounts.escrow;
+    let owner_wallet = &ctx.accounts.owner_wallet;
+    let mint = &ctx.accounts.mint;
+
+    deal.require_state(DealState::Open)?;
+
+    // Milestones are tied to the original offer.
+    if !deal.milestones.is_empty() {
+        return Err(error!(CustomError::InvalidMilestones));
+    }
+
+    let client_fee = ctx
+        .accounts
+        .mint_config
+        .client_fee(deal.client_fee_percentage, offer)?;
+
+    let total_escrow_amount = offer
+        .checked_add(client_fee)
+        .ok_or(error!(CustomError::MathOverflow))?;
+
+    let escrow_amount = match escrow {
+        Some(escrow) => escrow.amount,
+        None => escrowed_lamports(&deal.to_account_info())?,
+    };
+
+    deal.offer = offer;
+    deal.deadline = deadline;
+    deal.counter_offer = None;
+
+    emit!(OfferUpdated {
+        deal: deal.key(),
+        offer,
+        deadline,
+    });
+
+    match (&gig.mint, escrow, owner_wallet, mint) {
+        (Some(_), Some(escrow), Some(owner_wallet), Some(mint)) => {
+            if total_escrow_amount > escrow_amount {
+                // The client covers the transfer fee so the escrow receives the whole top up.
+                let top_up =
+                    amount_with_fee(&mint.to_account_info(), total_escrow_amount - escrow_amount)?;
+
+                let transfer_ix = TransferChecked {
+                    from: owner_wallet.to_account_info(),
+                    mint: mint.to_account_info(),
+                    to: escrow.to_account_info(),
+                    authority: client.to_account_info(),
+                };
+
+                let cpi_ctx =
+                    CpiContext::new(ctx.accounts.token_program.to_account_info(), transfer_ix);
+
+                anchor_spl::token_interface::transfer_checked(cpi_ctx, top_up, mint.decimals)?;
+            } else if total_escrow_amount < escrow_amount {
+                let gig_key = gig.key();
+                let client_key = client.key();
+                let deal_bump = deal.bump.to_le_bytes();
+
+                let inner = vec![
+                    b"deal".as_ref(),
+                    client_key.as_ref(),
+                    gig_key.as_ref(),
+                    deal.nonce.as_ref(),
+                    deal_bump.as_ref(),
+                ];
+
+                let deal_sig = vec![inner.as_slice()];
+
+                let transfer_ix = TransferChecked {
+                    from: escrow.to_account_info(),
+                    mint: mint.to_account_info(),
+                    to: owner_wallet.to_account_info(),
+                    authority: deal.to_account_info(),
+                };
+
+                let cpi_ctx = CpiContext::new_with_signer(
+                    ctx.accounts.token_program.to_account_info(),
+                    transfer_ix,
+                    deal_sig.as_slice(),
+                );
+
+                anchor_spl::token_interface::transfer_checked(
+                    cpi_ctx,
+                    escrow_amount - total_escrow_amount,
+                    mint.decimals,
+                )?;
+            }
+        }
+        (None, _, _, _) => {
+            if total_escrow_amount > escrow_amount {
+                let transfer_ix = system_program::Transfer {
+                    from: client.to_account_info(),
+                    to: deal.to_account_info(),
+                };
+
+                let cpi_ctx =
+                    CpiContext::new(ctx.accounts.system_program.to_account_info(), transfer_ix);
+
+                system_program::transfer(cpi_ctx, total_escrow_amount - escrow_amount)?;
+            } else if total_escrow_amount < escrow_amount {
+                transfer_lamports(
+                    &deal.to_account_info(),
+                    &client.to_account_info(),
+                    escrow_amount - total_escrow_amount,
+                )?;
+            }
+        }
+        _ => return Err(error!(CustomError::MissingTokenAccount)),
+    }
+
+    Ok(())
+}
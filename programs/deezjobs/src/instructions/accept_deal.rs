@@ -1,33 +1,63 @@
 use anchor_lang::{prelude::*, solana_program::clock};
 
-use crate::states::{Deal, Gig};
+use crate::{
+    events::DealAccepted,
+    states::{Config, Deal, DealState, Gig},
+    CustomError,
+};
+
+#[derive(AnchorDeserialize, AnchorSerialize)]
+pub struct AcceptDealParams {
+    /// Terms the freelancer agrees to, the client may have revised the offer in the meantime.
+    pub expected_offer: u64,
+    pub expected_deadline: i64,
+}
 
 #[derive(Accounts)]
+#[instruction(params: AcceptDealParams)]
 pub struct AcceptDeal<'info> {
     #[account(
         mut,
-        constraint = gig.owner.key() == freelancer.key(),
+        constraint = gig.owner.key() == freelancer.key() @ CustomError::Unauthorized,
     )]
     pub gig: Account<'info, Gig>,
 
     #[account(
         mut,
-        constraint = deal.gig.key() == gig.key(),
-        constraint = deal.state == 1,
+        constraint = deal.gig.key() == gig.key() @ CustomError::GigMismatch,
+        constraint = deal.offer == params.expected_offer && deal.deadline == params.expected_deadline @ CustomError::TermsMismatch,
     )]
     pub deal: Account<'info, Deal>,
 
     pub freelancer: Signer<'info>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = config.paused & 4 != 4 @ CustomError::Paused,
+    )]
+    pub config: Account<'info, Config>,
 }
 
-pub fn accept_deal_handler(ctx: Context<AcceptDeal>) -> Result<()> {
+pub fn accept_deal_handler(ctx: Context<AcceptDeal>, _params: AcceptDealParams) -> Result<()> {
     let clock = clock::Clock::get()?;
     let gig = &mut ctx.accounts.gig;
     let deal = &mut ctx.accounts.deal;
 
-    gig.pending_deals += 1;
+    deal.transition(DealState::Accepted)?;
+
+    gig.pending_deals = gig
+        .pending_deals
+        .checked_add(1)
+        .ok_or(error!(CustomError::MathOverflow))?;
     deal.time_accepted = clock.unix_timestamp;
-    deal.state = 3;
+    deal.counter_offer = None;
+
+    emit!(DealAccepted {
+        deal: deal.key(),
+        gig: gig.key(),
+        freelancer: deal.freelancer,
+    });
 
     Ok(())
 }
@@ -0,0 +1,60 @@
+use crate::{
+    states::{Config, MintConfig},
+    CustomError,
+};
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::Mint;
+
+#[derive(AnchorDeserialize, AnchorSerialize)]
+pub struct AddMintParams {
+    pub client_fee_min: u64,
+    pub client_fee_percentage: Option<u16>,
+    pub freelancer_fee_percentage: Option<u16>,
+    pub referral_fee_percentage: Option<u16>,
+}
+
+#[derive(Accounts)]
+#[instruction(params: AddMintParams)]
+pub struct AddMint<'info> {
+    #[account(
+        init,
+        payer = authority,
+        seeds = [
+            b"mint",
+            mint.key().as_ref(),
+        ],
+        bump,
+        space = MintConfig::len()
+    )]
+    pub mint_config: Account<'info, MintConfig>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        mut,
+        constraint = authority.key() == config.authority.key() @ CustomError::Unauthorized,
+    )]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn add_mint_handler(ctx: Context<AddMint>, params: AddMintParams) -> Result<()> {
+    let mint_config = &mut ctx.accounts.mint_config;
+
+    mint_config.bump = ctx.bumps.mint_config;
+    mint_config.mint = ctx.accounts.mint.key();
+    mint_config.enabled = true;
+    mint_config.client_fee_min = params.client_fee_min;
+    mint_config.client_fee_percentage = params.client_fee_percentage;
+    mint_config.freelancer_fee_percentage = params.freelancer_fee_percentage;
+    mint_config.referral_fee_percentage = params.referral_fee_percentage;
+
+    mint_config.validate_fees(&ctx.accounts.config)
+}
@@ -1,2 +0,0 @@
-// deal.state |= 4
-// callable by both freelancer and client
@@ -0,0 +1,40 @@
+use anchor_lang::{prelude::*, solana_program::clock};
+
+use crate::{
+    instructions::{release, CompleteDeal},
+    CustomError,
+};
+
+/// Anyone can complete a delivered deal once the client let the review window pass.
+pub fn auto_complete_handler(ctx: Context<CompleteDeal>) -> Result<()> {
+    let clock = clock::Clock::get()?;
+    let deal = &mut ctx.accounts.deal;
+
+    if deal.time_delivered == 0 {
+        return Err(error!(CustomError::NoDelivery));
+    }
+
+    let review_ends_at = deal
+        .time_delivered
+        .checked_add(deal.review_window)
+        .ok_or(error!(CustomError::MathOverflow))?;
+
+    if clock.unix_timestamp < review_ends_at {
+        return Err(error!(CustomError::ReviewWindowOpen));
+    }
+
+    // A delivery only covers the next milestone, the later ones need deliveries of their own.
+    let amount = match deal
+        .milestones
+        .iter_mut()
+        .find(|milestone| !milestone.released)
+    {
+        Some(milestone) => {
+            milestone.released = true;
+            milestone.amount
+        }
+        None => deal.remaining_offer(),
+    };
+
+    release(ctx, amount)
+}
@@ -0,0 +1,131 @@
+use anchor_lang::{prelude::*, solana_program::clock};
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token::{Mint, Token, TokenAccount, Transfer},
+};
+
+use crate::{
+    states::{Config, Deal, DealState, Dispute, DisputeVote},
+    CustomError,
+};
+
+#[derive(AnchorDeserialize, AnchorSerialize)]
+pub struct CastVoteParams {
+    /// Portion of the offer to refund to the client. Percentage with 2 decimal places (0 to 10000).
+    pub client_share: u16,
+    /// Amount of governance tokens to lock as voting power.
+    pub weight: u64,
+}
+
+#[derive(Accounts)]
+#[instruction(params: CastVoteParams)]
+pub struct CastVote<'info> {
+    #[account(
+        init,
+        payer = voter,
+        seeds = [
+            b"vote",
+            dispute.key().as_ref(),
+            voter.key().as_ref(),
+        ],
+        bump,
+        space = DisputeVote::len()
+    )]
+    pub vote: Box<Account<'info, DisputeVote>>,
+
+    #[account(
+        init,
+        payer = voter,
+        associated_token::mint = governance_mint,
+        associated_token::authority = vote,
+    )]
+    pub vote_vault: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        associated_token::mint = governance_mint,
+        associated_token::authority = voter,
+    )]
+    pub voter_wallet: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"dispute",
+            deal.key().as_ref(),
+        ],
+        bump = dispute.bump,
+        constraint = params.client_share <= 100_00 @ CustomError::InvalidShare,
+        constraint = params.weight > 0 @ CustomError::InvalidVoteWeight,
+    )]
+    pub dispute: Box<Account<'info, Dispute>>,
+
+    #[account(
+        constraint = deal.state == DealState::Disputed @ CustomError::InvalidDealState,
+    )]
+    pub deal: Box<Account<'info, Deal>>,
+
+    #[account(
+        constraint = governance_mint.key() == dispute.governance_mint.key() @ CustomError::InvalidGovernanceMint,
+    )]
+    pub governance_mint: Box<Account<'info, Mint>>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Box<Account<'info, Config>>,
+
+    // Parties of the deal cannot vote on their own dispute.
+    #[account(
+        mut,
+        constraint = voter.key() != deal.client.key() && voter.key() != deal.freelancer.key() @ CustomError::PartyCannotVote,
+    )]
+    pub voter: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+pub fn cast_vote_handler(ctx: Context<CastVote>, params: CastVoteParams) -> Result<()> {
+    let clock = clock::Clock::get()?;
+    let vote = &mut ctx.accounts.vote;
+    let dispute = &mut ctx.accounts.dispute;
+    let voter = &ctx.accounts.voter;
+
+    if clock.unix_timestamp >= dispute.voting_ends_at {
+        return Err(error!(CustomError::VotingClosed));
+    }
+
+    // Lock the voting power until the voting window closes.
+
+    let transfer_ix = Transfer {
+        from: ctx.accounts.voter_wallet.to_account_info(),
+        to: ctx.accounts.vote_vault.to_account_info(),
+        authority: voter.to_account_info(),
+    };
+
+    let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), transfer_ix);
+
+    anchor_spl::token::transfer(cpi_ctx, params.weight)?;
+
+    vote.bump = ctx.bumps.vote;
+    vote.dispute = dispute.key();
+    vote.voter = voter.key();
+    vote.weight = params.weight;
+    vote.client_share = params.client_share;
+    vote.voting_ends_at = dispute.voting_ends_at;
+
+    dispute.total_weight = dispute
+        .total_weight
+        .checked_add(params.weight)
+        .ok_or(error!(CustomError::MathOverflow))?;
+    dispute.weighted_client_share = dispute
+        .weighted_client_share
+        .checked_add(u128::from(params.weight) * u128::from(params.client_share))
+        .ok_or(error!(CustomError::MathOverflow))?;
+
+    Ok(())
+}
@@ -0,0 +1,131 @@
+use anchor_lang::{prelude::*, solana_program::clock};
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{Mint, TokenAccount, TokenInterface},
+};
+
+use crate::{
+    events::DealExpired,
+    states::{Deal, DealState, Gig, Profile},
+    utils::{close_program_account, refund_escrow},
+    CustomError,
+};
+
+#[derive(Accounts)]
+pub struct ClaimExpired<'info> {
+    #[account(
+        mut,
+        constraint = deal.client.key() == client.key() @ CustomError::Unauthorized,
+        constraint = deal.time_delivered == 0 @ CustomError::DealDelivered,
+    )]
+    pub deal: Account<'info, Deal>,
+
+    #[account(
+        mut,
+        constraint = gig.key() == deal.gig.key() @ CustomError::GigMismatch,
+        constraint = gig.mint == mint.as_ref().map(|mint| mint.key()) @ CustomError::MintMismatch,
+    )]
+    pub gig: Account<'info, Gig>,
+
+    #[account(
+        init_if_needed,
+        payer = client,
+        seeds = [
+            b"profile",
+            deal.freelancer.as_ref(),
+        ],
+        bump,
+        space = Profile::len()
+    )]
+    pub freelancer_profile: Box<Account<'info, Profile>>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = deal,
+        associated_token::token_program = token_program,
+    )]
+    pub escrow: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = client,
+        token::token_program = token_program,
+    )]
+    pub client_wallet: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    // Omitted if the gig is paid in SOL.
+    #[account(mut)]
+    pub mint: Option<InterfaceAccount<'info, Mint>>,
+
+    #[account(mut)]
+    pub client: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
+
+/// Refunds whatever is left in escrow once the freelancer missed the deadline, or the due date of a
+/// milestone, without delivering.
+pub fn claim_expired_handler(ctx: Context<ClaimExpired>) -> Result<()> {
+    let clock = clock::Clock::get()?;
+    let client = &mut ctx.accounts.client;
+    let deal = &mut ctx.accounts.deal;
+    let gig = &mut ctx.accounts.gig;
+
+    if clock.unix_timestamp <= deal.expires_at() {
+        return Err(error!(CustomError::DeadlineNotReached));
+    }
+
+    deal.transition(DealState::Expired)?;
+    gig.pending_deals = gig
+        .pending_deals
+        .checked_sub(1)
+        .ok_or(error!(CustomError::MathOverflow))?;
+
+    let freelancer_profile = &mut ctx.accounts.freelancer_profile;
+    freelancer_profile.register(ctx.bumps.freelancer_profile, deal.freelancer);
+    freelancer_profile.record_delivery(false)?;
+
+    // Refund the escrow to the client
+    // SOL escrow is refunded as soon as the deal account is closed.
+
+    if gig.mint.is_some() {
+        let escrow = ctx
+            .accounts
+            .escrow
+            .as_ref()
+            .ok_or(error!(CustomError::MissingTokenAccount))?;
+
+        let mint = ctx
+            .accounts
+            .mint
+            .as_ref()
+            .ok_or(error!(CustomError::MintMismatch))?;
+
+        let client_wallet = ctx
+            .accounts
+            .client_wallet
+            .as_ref()
+            .ok_or(error!(CustomError::MissingTokenAccount))?;
+
+        refund_escrow(
+            deal,
+            escrow,
+            mint,
+            client_wallet,
+            &client.to_account_info(),
+            &ctx.accounts.token_program.to_account_info(),
+        )?;
+    }
+
+    emit!(DealExpired {
+        deal: deal.key(),
+        gig: gig.key(),
+        client: client.key(),
+    });
+
+    close_program_account(deal, &client.to_account_info())
+}
@@ -1,27 +1,30 @@
 use anchor_lang::prelude::*;
 use anchor_spl::{
     associated_token::AssociatedToken,
-    token::{Mint, Token, TokenAccount, Transfer, CloseAccount},
+    token_interface::{Mint, TokenAccount, TokenInterface},
 };
 
-use crate::states::{Deal, Gig};
+use crate::{
+    events::DealClosed,
+    states::{Deal, DealState, Gig},
+    utils::{close_program_account, refund_escrow},
+    CustomError,
+};
 
 #[derive(Accounts)]
 pub struct CloseDeal<'info> {
-    #[account(
-        mut,
-        constraint = deal.state & 2 != 2,
-    )]
+    #[account(mut)]
     pub deal: Account<'info, Deal>,
 
     #[account(
-        constraint = gig.key() == deal.gig.key(),
+        constraint = gig.key() == deal.gig.key() @ CustomError::GigMismatch,
+        constraint = gig.mint == mint.as_ref().map(|mint| mint.key()) @ CustomError::MintMismatch,
     )]
     pub gig: Account<'info, Gig>,
 
     #[account(
         mut,
-        constraint = client.key() == deal.client.key()
+        constraint = client.key() == deal.client.key() @ CustomError::ClientMismatch,
     )]
     /// CHECK: should be the owner of the deal
     pub client: UncheckedAccount<'info>,
@@ -29,97 +32,77 @@ pub struct CloseDeal<'info> {
     #[account(
         mut,
         associated_token::mint = mint,
-        associated_token::authority = deal,      
+        associated_token::authority = deal,
+        associated_token::token_program = token_program,
     )]
-    pub escrow: Account<'info, TokenAccount>,
+    pub escrow: Option<InterfaceAccount<'info, TokenAccount>>,
 
     #[account(
         mut,
-        constraint = owner_wallet.owner == client.key(),
-        constraint = owner_wallet.mint == mint.key(),
+        token::mint = mint,
+        token::authority = client,
+        token::token_program = token_program,
     )]
-    pub owner_wallet: Account<'info, TokenAccount>,
+    pub owner_wallet: Option<InterfaceAccount<'info, TokenAccount>>,
 
-    #[account(
-        constraint = mint.key() == gig.mint.unwrap().key(),
-    )]
-    pub mint: Account<'info, Mint>,
+    // Omitted if the gig is paid in SOL.
+    #[account(mut)]
+    pub mint: Option<InterfaceAccount<'info, Mint>>,
 
     #[account(
         mut,
-        constraint = signer.key() == deal.client.key() || signer.key() == gig.owner.key(),
+        constraint = signer.key() == deal.client.key() || signer.key() == gig.owner.key() @ CustomError::Unauthorized,
     )]
     pub signer: Signer<'info>,
 
-    pub token_program: Program<'info, Token>,
+    pub token_program: Interface<'info, TokenInterface>,
     pub associated_token_program: Program<'info, AssociatedToken>,
 }
 
 pub fn close_deal_handler(ctx: Context<CloseDeal>) -> Result<()> {
     let client = &mut ctx.accounts.client;
     let deal = &mut ctx.accounts.deal;
-    let client_wallet = &mut ctx.accounts.owner_wallet;
-    let escrow = &mut ctx.accounts.escrow;
     let gig = &ctx.accounts.gig;
 
-    // Transfer escrow funds back to the client
-
-    let gig_key = gig.key();
-    let client_key = client.key();
-    let deal_bump = deal.bump.to_le_bytes();
-
-    let inner = vec![
-        b"deal".as_ref(),
-        client_key.as_ref(),
-        gig_key.as_ref(),
-        deal_bump.as_ref(),
-    ];
-
-    let deal_sig = vec![inner.as_slice()];
-
-    let transfer_ix = Transfer {
-        from: escrow.to_account_info(),
-        to: client_wallet.to_account_info(),
-        authority: deal.to_account_info(),
-    };
+    deal.transition(DealState::Cancelled)?;
 
-    let cpi_ctx = CpiContext::new_with_signer(
-        ctx.accounts.token_program.to_account_info(),
-        transfer_ix,
-        deal_sig.as_slice(),
-    );
-
-    anchor_spl::token::transfer(cpi_ctx, escrow.amount)?;
-
-    // Close escrow account
-
-    let cpi_close = CloseAccount {
-        account: escrow.to_account_info(),
-        destination: client.to_account_info(),
-        authority: deal.to_account_info(),
-    };
-
-    let cpi_ctx = CpiContext::new_with_signer(
-        ctx.accounts.token_program.to_account_info(), 
-        cpi_close, 
-        deal_sig.as_slice()
-    );
-
-    anchor_spl::token::close_account(cpi_ctx)?;
-
-    // Close deal account
-
-    let source_account_info = deal.to_account_info();
-    let dest_account_info = client.to_account_info();
-
-    let dest_starting_lamports = dest_account_info.lamports();
-    **dest_account_info.lamports.borrow_mut() = dest_starting_lamports
-        .checked_add(source_account_info.lamports())
-        .unwrap();
-    **source_account_info.lamports.borrow_mut() = 0;
-
-    let mut source_data = source_account_info.data.borrow_mut();
-    source_data.fill(0);
-
-    Ok(())
+    // Transfer escrow funds back to the client
+    // SOL escrow is refunded as soon as the deal account is closed.
+
+    if gig.mint.is_some() {
+        let escrow = ctx
+            .accounts
+            .escrow
+            .as_ref()
+            .ok_or(error!(CustomError::MissingTokenAccount))?;
+
+        let mint = ctx
+            .accounts
+            .mint
+            .as_ref()
+            .ok_or(error!(CustomError::MintMismatch))?;
+
+        let client_wallet = ctx
+            .accounts
+            .owner_wallet
+            .as_ref()
+            .ok_or(error!(CustomError::MissingTokenAccount))?;
+
+        refund_escrow(
+            deal,
+            escrow,
+            mint,
+            client_wallet,
+            &client.to_account_info(),
+            &ctx.accounts.token_program.to_account_info(),
+        )?;
+    }
+
+    emit!(DealClosed {
+        deal: deal.key(),
+        gig: gig.key(),
+        client: client.key(),
+    });
+
+    close_program_account(deal, &client.to_account_info())
 }
@@ -1,81 +1,188 @@
-use anchor_lang::prelude::*;
-use anchor_spl::{token::{Mint, TokenAccount, Token, Transfer, CloseAccount}, associated_token::AssociatedToken};
-
-use crate::states::{Config, Gig, Deal};
+use anchor_lang::{prelude::*, solana_program::clock};
+use anchor_spl::{
+    associated_token::{get_associated_token_address_with_program_id, AssociatedToken},
+    token_2022::{self, Token2022},
+    token_interface::{Mint, TokenAccount, TokenInterface, TransferChecked},
+};
+
+use crate::{
+    credential::{self, Credential, CredentialAuthority},
+    events::{DealCompleted, MilestoneReleased},
+    states::{Config, Deal, DealState, Gig, Profile, Receipt},
+    utils::{
+        close_escrow, close_program_account, escrowed_lamports, transfer_lamports, withheld_fee,
+    },
+    CustomError,
+};
 
 #[derive(Accounts)]
 pub struct CompleteDeal<'info> {
     #[account(
         mut,
-        constraint = gig.key() == deal.gig.key(),
+        constraint = gig.key() == deal.gig.key() @ CustomError::GigMismatch,
+        constraint = gig.mint == mint.as_ref().map(|mint| mint.key()) @ CustomError::MintMismatch,
     )]
     pub gig: Box<Account<'info, Gig>>,
 
+    #[account(mut)]
+    pub deal: Box<Account<'info, Deal>>,
+
     #[account(
-        mut,
-        constraint = deal.state & 2 == 2,
+        init_if_needed,
+        payer = signer,
+        seeds = [
+            b"receipt",
+            deal.key().as_ref(),
+        ],
+        bump,
+        space = Receipt::len()
     )]
-    pub deal: Box<Account<'info, Deal>>,
+    pub receipt: Box<Account<'info, Receipt>>,
 
     #[account(
-        constraint = freelancer.key() == gig.owner.key(),
+        mut,
+        constraint = freelancer.key() == gig.owner.key() @ CustomError::FreelancerMismatch,
     )]
     /// CHECK:
     pub freelancer: UncheckedAccount<'info>,
 
+    // Both profiles are loaded, the write of one would overwrite the other.
     #[account(
         mut,
-        constraint = client.key() == deal.client.key(),
+        constraint = client.key() == deal.client.key() @ CustomError::ClientMismatch,
+        constraint = client.key() != freelancer.key() @ CustomError::SelfDeal,
     )]
     /// CHECK:
     pub client: UncheckedAccount<'info>,
 
     #[account(
-        constraint = mint.key() == gig.mint.unwrap().key(),
+        init_if_needed,
+        payer = signer,
+        seeds = [
+            b"profile",
+            freelancer.key().as_ref(),
+        ],
+        bump,
+        space = Profile::len()
+    )]
+    pub freelancer_profile: Box<Account<'info, Profile>>,
+
+    #[account(
+        init_if_needed,
+        payer = signer,
+        seeds = [
+            b"profile",
+            client.key().as_ref(),
+        ],
+        bump,
+        space = Profile::len()
+    )]
+    pub client_profile: Box<Account<'info, Profile>>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"credential",
+            freelancer.key().as_ref(),
+        ],
+        bump,
+    )]
+    /// CHECK: See `Credential`.
+    pub freelancer_credential: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        address = get_associated_token_address_with_program_id(
+            &freelancer.key(),
+            &freelancer_credential.key(),
+            &token_2022::ID,
+        ) @ CustomError::CredentialMismatch,
+    )]
+    /// CHECK: See `Credential`.
+    pub freelancer_credential_account: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"credential",
+            client.key().as_ref(),
+        ],
+        bump,
+    )]
+    /// CHECK: See `Credential`.
+    pub client_credential: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        address = get_associated_token_address_with_program_id(
+            &client.key(),
+            &client_credential.key(),
+            &token_2022::ID,
+        ) @ CustomError::CredentialMismatch,
     )]
-    pub mint: Box<Account<'info, Mint>>,
+    /// CHECK: See `Credential`.
+    pub client_credential_account: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"credential_collection"],
+        bump,
+    )]
+    /// CHECK: See `CredentialAuthority`.
+    pub credential_collection: UncheckedAccount<'info>,
+
+    // Omitted along with the token accounts if the gig is paid in SOL.
+    #[account(mut)]
+    pub mint: Option<Box<InterfaceAccount<'info, Mint>>>,
 
     #[account(
         mut,
         associated_token::mint = mint,
         associated_token::authority = deal,
+        associated_token::token_program = token_program,
     )]
-    pub escrow: Box<Account<'info, TokenAccount>>,
+    pub escrow: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
 
     #[account(
         init_if_needed,
-        payer = client, 
-        associated_token::mint = mint, 
+        payer = signer,
+        associated_token::mint = mint,
         associated_token::authority = freelancer,
+        associated_token::token_program = token_program,
     )]
-    pub freelancer_token_account: Box<Account<'info, TokenAccount>>,
+    pub freelancer_token_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
 
     #[account(
-        constraint = referrer.key() == deal.referrer.unwrap().key(),
+        mut,
+        constraint = deal.referrer == Some(referrer.key()) @ CustomError::ReferrerMismatch,
     )]
     /// CHECK:
     pub referrer: Option<UncheckedAccount<'info>>,
 
     #[account(
         init_if_needed,
-        payer = client, 
-        associated_token::mint = mint, 
+        payer = signer,
+        associated_token::mint = mint,
         associated_token::authority = referrer,
+        associated_token::token_program = token_program,
     )]
-    pub referrer_token_account: Option<Box<Account<'info, TokenAccount>>>,
+    pub referrer_token_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
 
     #[account(
-        constraint = treasury.key() == config.treasury.key(),
+        mut,
+        constraint = treasury.key() == config.treasury.key() @ CustomError::TreasuryMismatch,
     )]
     /// CHECK:
     pub treasury: UncheckedAccount<'info>,
 
     #[account(
-        mut,
-        associated_token::mint = mint, 
+        init_if_needed,
+        payer = signer,
+        associated_token::mint = mint,
         associated_token::authority = treasury,
+        associated_token::token_program = token_program,
     )]
-    pub treasury_token_account: Account<'info, TokenAccount>,
+    pub treasury_token_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
 
     #[account(
         seeds = [b"config"],
@@ -83,35 +190,291 @@ pub struct CompleteDeal<'info> {
     )]
     pub config: Box<Account<'info, Config>>,
 
-    #[account(
-        mut,
-        constraint = signer.key() == client.key() || signer.key() == config.authority.key(),
-    )]
+    #[account(mut)]
     pub signer: Signer<'info>,
 
     pub system_program: Program<'info, System>,
-    pub token_program: Program<'info, Token>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub token_2022_program: Program<'info, Token2022>,
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub rent: Sysvar<'info, Rent>,
 }
 
 pub fn complete_deal_handler(ctx: Context<CompleteDeal>) -> Result<()> {
+    require_client_or_authority(&ctx)?;
+
+    let deal = &mut ctx.accounts.deal;
+
+    let amount = deal.remaining_offer();
+
+    for milestone in deal.milestones.iter_mut() {
+        milestone.released = true;
+    }
+
+    release(ctx, amount)
+}
 
+/// Only the client or the authority can release the escrow, `auto_complete` excepted.
+pub fn require_client_or_authority(ctx: &Context<CompleteDeal>) -> Result<()> {
+    let signer = ctx.accounts.signer.key();
+
+    if signer != ctx.accounts.client.key() && signer != ctx.accounts.config.authority.key() {
+        return Err(error!(CustomError::Unauthorized));
+    }
+
+    Ok(())
+}
+
+/// Pays out `amount` of the offer, which must already be marked as released on the deal.
+/// Once nothing is left to release, the escrow and the deal are closed and only the receipt remains.
+pub fn release(ctx: Context<CompleteDeal>, amount: u64) -> Result<()> {
+    let clock = clock::Clock::get()?;
     let gig = &mut ctx.accounts.gig;
     let deal = &mut ctx.accounts.deal;
+    let receipt = &mut ctx.accounts.receipt;
     let client = &mut ctx.accounts.client;
     let config = &ctx.accounts.config;
 
-    let escrow = &mut ctx.accounts.escrow;
-    let freelancer_token_account = &mut ctx.accounts.freelancer_token_account;
-    let referrer_token_account = &mut ctx.accounts.referrer_token_account;
-    let treasury_token_account = &mut ctx.accounts.treasury_token_account;
+    let escrow = &ctx.accounts.escrow;
+    let referrer = &ctx.accounts.referrer;
+    let referrer_token_account = &ctx.accounts.referrer_token_account;
+
+    let remaining_offer = deal.remaining_offer();
+    let is_last = deal.is_fully_released();
+
+    // The profiles may be created by any payout, not only the last one.
+    let freelancer_profile = &mut ctx.accounts.freelancer_profile;
+    freelancer_profile.register(ctx.bumps.freelancer_profile, deal.freelancer);
+    let client_profile = &mut ctx.accounts.client_profile;
+    client_profile.register(ctx.bumps.client_profile, deal.client);
+
+    if is_last {
+        deal.transition(DealState::Completed)?;
+        gig.pending_deals = gig
+            .pending_deals
+            .checked_sub(1)
+            .ok_or(error!(CustomError::MathOverflow))?;
+        receipt.time_completed = clock.unix_timestamp;
+
+        // Deals completed without a submitted delivery count as delivered now.
+        let time_delivered = match deal.time_delivered {
+            0 => clock.unix_timestamp,
+            time_delivered => time_delivered,
+        };
+
+        freelancer_profile.record_completed(gig.payment_mint(), deal.offer)?;
+        freelancer_profile.record_delivery(time_delivered <= deal.deadline)?;
+
+        client_profile.record_completed(gig.payment_mint(), deal.offer)?;
+
+        let authority = CredentialAuthority {
+            config: config.to_account_info(),
+            config_bump: config.bump,
+            collection: ctx.accounts.credential_collection.to_account_info(),
+            payer: ctx.accounts.signer.to_account_info(),
+            token_program: ctx.accounts.token_2022_program.to_account_info(),
+            associated_token_program: ctx.accounts.associated_token_program.to_account_info(),
+            system_program: ctx.accounts.system_program.to_account_info(),
+        };
+
+        Credential {
+            holder: ctx.accounts.freelancer.to_account_info(),
+            mint: ctx.accounts.freelancer_credential.to_account_info(),
+            mint_bump: ctx.bumps.freelancer_credential,
+            token_account: ctx.accounts.freelancer_credential_account.to_account_info(),
+        }
+        .sync(
+            &authority,
+            freelancer_profile,
+            &[
+                credential::COMPLETED_DEALS,
+                credential::DELIVERIES,
+                credential::ON_TIME_DELIVERIES,
+            ],
+        )?;
+
+        Credential {
+            holder: client.to_account_info(),
+            mint: ctx.accounts.client_credential.to_account_info(),
+            mint_bump: ctx.bumps.client_credential,
+            token_account: ctx.accounts.client_credential_account.to_account_info(),
+        }
+        .sync(&authority, client_profile, &[credential::COMPLETED_DEALS])?;
+    } else {
+        deal.require_state(DealState::Accepted)?;
+
+        // The next milestone is delivered and reviewed on its own.
+        deal.delivery = [0; 32];
+        deal.time_delivered = 0;
+    }
 
-    gig.pending_deals -= 1;
-    deal.state |= 8;
+    receipt.bump = ctx.bumps.receipt;
+    receipt.deal = deal.key();
+    receipt.gig = gig.key();
+    receipt.client = client.key();
+    receipt.freelancer = deal.freelancer;
+    receipt.mint = gig.mint;
+    receipt.offer = deal.offer;
+    receipt.released = receipt
+        .released
+        .checked_add(amount)
+        .ok_or(error!(CustomError::MathOverflow))?;
 
     // Compute fees.
 
+    // The referral bounty is owed whenever the deal was referred, leaving out the referrer's
+    // accounts must not move it to the treasury.
+    let has_referrer = deal.referrer.is_some();
+
+    let is_referrer_missing = match gig.mint {
+        Some(_) => referrer_token_account.is_none(),
+        None => referrer.is_none(),
+    };
+
+    if has_referrer && is_referrer_missing {
+        return Err(error!(CustomError::ReferrerMismatch));
+    }
+
+    let escrow_amount = match escrow {
+        Some(escrow) => escrow.amount,
+        None => escrowed_lamports(&deal.to_account_info())?,
+    };
+
+    let freelancer_fee_percentage: u64 = deal.freelancer_fee_percentage.into();
+
+    let freelancer_fee = freelancer_fee_percentage
+        .checked_mul(amount)
+        .ok_or(error!(CustomError::MathOverflow))?
+        .checked_div(100_00)
+        .ok_or(error!(CustomError::MathOverflow))?;
+
+    let referral_pay = if has_referrer {
+        let referral_fee_percentage: u64 = deal.referral_fee_percentage.into();
+
+        referral_fee_percentage
+            .checked_mul(amount)
+            .ok_or(error!(CustomError::MathOverflow))?
+            .checked_div(100_00)
+            .ok_or(error!(CustomError::MathOverflow))?
+    } else {
+        0
+    };
+
+    let freelancer_pay = amount
+        .checked_sub(freelancer_fee)
+        .and_then(|pay| pay.checked_sub(referral_pay))
+        .ok_or(error!(CustomError::MathOverflow))?;
+
+    let treasury_pay = if is_last {
+        // Remainder of the escrow amount, this should include the client's fee as well.
+        escrow_amount
+            .checked_sub(freelancer_pay)
+            .and_then(|pay| pay.checked_sub(referral_pay))
+            .ok_or(error!(CustomError::MathOverflow))?
+    } else {
+        // The client's fee is charged in proportion to the released amount.
+        let escrowed_offer = remaining_offer
+            .checked_add(amount)
+            .ok_or(error!(CustomError::MathOverflow))?;
+        let client_fee = escrow_amount
+            .checked_sub(escrowed_offer)
+            .ok_or(error!(CustomError::MathOverflow))?
+            .checked_mul(amount)
+            .ok_or(error!(CustomError::MathOverflow))?
+            .checked_div(escrowed_offer)
+            .ok_or(error!(CustomError::MathOverflow))?;
+
+        freelancer_fee
+            .checked_add(client_fee)
+            .ok_or(error!(CustomError::MathOverflow))?
+    };
+
+    let client_fee = treasury_pay
+        .checked_sub(freelancer_fee)
+        .ok_or(error!(CustomError::MathOverflow))?;
+
+    let withheld_fee = withheld_fee(
+        ctx.accounts
+            .mint
+            .as_ref()
+            .map(|mint| mint.to_account_info()),
+        &[freelancer_pay, referral_pay, treasury_pay],
+    )?;
+
+    if is_last {
+        emit!(DealCompleted {
+            deal: deal.key(),
+            gig: gig.key(),
+            client: client.key(),
+            freelancer: deal.freelancer,
+            freelancer_pay,
+            referral_pay,
+            treasury_pay,
+            client_fee,
+            withheld_fee,
+        });
+    } else {
+        emit!(MilestoneReleased {
+            deal: deal.key(),
+            amount,
+            freelancer_pay,
+            referral_pay,
+            treasury_pay,
+            client_fee,
+            withheld_fee,
+        });
+    }
+
+    if gig.mint.is_none() {
+        // SOL is escrowed in the deal account, its rent is left untouched.
+
+        let deal_info = deal.to_account_info();
+
+        transfer_lamports(
+            &deal_info,
+            &ctx.accounts.freelancer.to_account_info(),
+            freelancer_pay,
+        )?;
+
+        if let Some(referrer) = referrer {
+            transfer_lamports(&deal_info, &referrer.to_account_info(), referral_pay)?;
+        }
+
+        transfer_lamports(
+            &deal_info,
+            &ctx.accounts.treasury.to_account_info(),
+            treasury_pay,
+        )?;
+
+        if is_last {
+            close_program_account(deal, &client.to_account_info())?;
+        }
+
+        return Ok(());
+    }
+
+    let escrow = escrow
+        .as_ref()
+        .ok_or(error!(CustomError::MissingTokenAccount))?;
+
+    let mint = ctx
+        .accounts
+        .mint
+        .as_ref()
+        .ok_or(error!(CustomError::MintMismatch))?;
+
+    let freelancer_token_account = ctx
+        .accounts
+        .freelancer_token_account
+        .as_ref()
+        .ok_or(error!(CustomError::MissingTokenAccount))?;
+
+    let treasury_token_account = ctx
+        .accounts
+        .treasury_token_account
+        .as_ref()
+        .ok_or(error!(CustomError::MissingTokenAccount))?;
+
     let gig_key = gig.key();
     let client_key = client.key();
     let deal_bump = deal.bump.to_le_bytes();
@@ -120,39 +483,17 @@ pub fn complete_deal_handler(ctx: Context<CompleteDeal>) -> Result<()> {
         b"deal".as_ref(),
         client_key.as_ref(),
         gig_key.as_ref(),
+        deal.nonce.as_ref(),
         deal_bump.as_ref(),
     ];
 
     let deal_sig = vec![inner.as_slice()];
 
-    let freelancer_fee_percentage: u64 = config.freelancer_fee_percentage.try_into().unwrap();    
-
-    let freelancer_fee = freelancer_fee_percentage
-        .checked_mul(deal.offer)
-        .unwrap()
-        .checked_div(100_00)
-        .unwrap();
-
-    let referral_pay = match referrer_token_account {
-        Some(_) => {
-            let referral_fee_percentage: u64 = config.referral_fee_percentage.try_into().unwrap();    
-
-            referral_fee_percentage
-                .checked_mul(deal.offer)
-                .unwrap()
-                .checked_div(100_00)
-                .unwrap()
-        },
-        None => 0,
-    };
-
-    let freelancer_pay = deal.offer - freelancer_fee - referral_pay;
-    let treasury_pay = escrow.amount - freelancer_pay - referral_pay;
-
     // Transfer to freelancer.
 
-    let transfer_ix = Transfer {
+    let transfer_ix = TransferChecked {
         from: escrow.to_account_info(),
+        mint: mint.to_account_info(),
         to: freelancer_token_account.to_account_info(),
         authority: deal.to_account_info(),
     };
@@ -163,34 +504,32 @@ pub fn complete_deal_handler(ctx: Context<CompleteDeal>) -> Result<()> {
         deal_sig.as_slice(),
     );
 
-    anchor_spl::token::transfer(cpi_ctx, freelancer_pay)?;
+    anchor_spl::token_interface::transfer_checked(cpi_ctx, freelancer_pay, mint.decimals)?;
 
     // Transfer to referrer.
 
-    match referrer_token_account {
-        Some(referrer_token_account) => {
-            let transfer_ix = Transfer {
-                from: escrow.to_account_info(),
-                to: referrer_token_account.to_account_info(),
-                authority: deal.to_account_info(),
-            };
-        
-            let cpi_ctx = CpiContext::new_with_signer(
-                ctx.accounts.token_program.to_account_info(),
-                transfer_ix,
-                deal_sig.as_slice(),
-            );
-        
-            anchor_spl::token::transfer(cpi_ctx, referral_pay)?;
-        },
-        None => (),
+    if let Some(referrer_token_account) = referrer_token_account {
+        let transfer_ix = TransferChecked {
+            from: escrow.to_account_info(),
+            mint: mint.to_account_info(),
+            to: referrer_token_account.to_account_info(),
+            authority: deal.to_account_info(),
+        };
+
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            transfer_ix,
+            deal_sig.as_slice(),
+        );
+
+        anchor_spl::token_interface::transfer_checked(cpi_ctx, referral_pay, mint.decimals)?;
     }
 
     // Transfer to treasury.
-    // Remainder of the escrow amount, this should include the client's fee as well.
 
-    let transfer_ix = Transfer {
+    let transfer_ix = TransferChecked {
         from: escrow.to_account_info(),
+        mint: mint.to_account_info(),
         to: treasury_token_account.to_account_info(),
         authority: deal.to_account_info(),
     };
@@ -201,23 +540,21 @@ pub fn complete_deal_handler(ctx: Context<CompleteDeal>) -> Result<()> {
         deal_sig.as_slice(),
     );
 
-    anchor_spl::token::transfer(cpi_ctx, treasury_pay)?;
-    
-    // Close escrow account, give back the rent to client.
+    anchor_spl::token_interface::transfer_checked(cpi_ctx, treasury_pay, mint.decimals)?;
 
-    let cpi_close = CloseAccount {
-        account: escrow.to_account_info(),
-        destination: client.to_account_info(),
-        authority: deal.to_account_info(),
-    };
+    if !is_last {
+        return Ok(());
+    }
 
-    let cpi_ctx = CpiContext::new_with_signer(
-        ctx.accounts.token_program.to_account_info(), 
-        cpi_close, 
-        deal_sig.as_slice()
-    );
+    // Close escrow account, give back the rent to client.
 
-    anchor_spl::token::close_account(cpi_ctx)?;
+    close_escrow(
+        deal,
+        escrow,
+        mint,
+        &client.to_account_info(),
+        &ctx.accounts.token_program.to_account_info(),
+    )?;
 
-    Ok(())
-}
\ No newline at end of file
+    close_program_account(deal, &client.to_account_info())
+}
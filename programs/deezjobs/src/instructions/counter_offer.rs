@@ -0,0 +1,64 @@
+use anchor_lang::{prelude::*, solana_program::clock};
+
+use crate::{
+    events::CounterOffered,
+    states::{Deal, DealState, Gig},
+    CustomError,
+};
+
+#[derive(AnchorDeserialize, AnchorSerialize)]
+pub struct CounterOfferParams {
+    pub offer: u64,
+    pub deadline: i64,
+}
+
+#[derive(Accounts)]
+#[instruction(params: CounterOfferParams)]
+pub struct CounterOffer<'info> {
+    #[account(
+        constraint = gig.owner.key() == freelancer.key() @ CustomError::Unauthorized,
+    )]
+    pub gig: Account<'info, Gig>,
+
+    #[account(
+        mut,
+        constraint = deal.gig.key() == gig.key() @ CustomError::GigMismatch,
+    )]
+    pub deal: Account<'info, Deal>,
+
+    pub freelancer: Signer<'info>,
+}
+
+/// Proposing again replaces the previous counter offer.
+pub fn counter_offer_handler(ctx: Context<CounterOffer>, params: CounterOfferParams) -> Result<()> {
+    let clock = clock::Clock::get()?;
+    let gig = &ctx.accounts.gig;
+    let deal = &mut ctx.accounts.deal;
+
+    deal.require_state(DealState::Open)?;
+
+    // Milestones are tied to the original offer, the client would never be able to accept.
+    if !deal.milestones.is_empty() {
+        return Err(error!(CustomError::InvalidMilestones));
+    }
+
+    let min_deadline = clock
+        .unix_timestamp
+        .checked_add(gig.min_completion_time)
+        .ok_or(error!(CustomError::MathOverflow))?;
+
+    if min_deadline > params.deadline {
+        return Err(error!(CustomError::DeadlineTooShort));
+    }
+
+    deal.counter_offer = Some(params.offer);
+    deal.counter_deadline = params.deadline;
+
+    emit!(CounterOffered {
+        deal: deal.key(),
+        offer: params.offer,
+        deadline: params.deadline,
+    });
+
+    Ok(())
+}
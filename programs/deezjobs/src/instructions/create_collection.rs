@@ -0,0 +1,149 @@
+use crate::{
+    credential::{NAME, SYMBOL},
+    states::Config,
+    utils::create_pda_account,
+    CustomError,
+};
+use anchor_lang::{
+    prelude::*,
+    solana_program::program::{invoke, invoke_signed},
+};
+use anchor_spl::token_2022::{self, InitializeMint2, Token2022};
+use spl_token_2022::{
+    extension::{group_pointer, metadata_pointer, ExtensionType},
+    state::Mint,
+};
+use spl_token_group_interface::instruction as group_instruction;
+use spl_token_metadata_interface::{instruction as metadata_instruction, state::TokenMetadata};
+
+#[derive(Accounts)]
+pub struct CreateCollection<'info> {
+    #[account(
+        mut,
+        seeds = [b"credential_collection"],
+        bump,
+    )]
+    /// CHECK: Created by the handler, fails if the collection already exists.
+    pub collection: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        mut,
+        constraint = authority.key() == config.authority.key() @ CustomError::Unauthorized,
+    )]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token2022>,
+}
+
+/// The collection is a Token-2022 mint holding the token group of the credentials, it has no supply
+/// and the Config PDA stays its mint authority so that only the program adds members.
+pub fn create_collection_handler(ctx: Context<CreateCollection>) -> Result<()> {
+    let collection = &ctx.accounts.collection;
+    let config = &ctx.accounts.config;
+
+    let collection_key = collection.key();
+    let config_key = config.key();
+    let collection_bump = [ctx.bumps.collection];
+    let config_bump = [config.bump];
+    let collection_seeds: &[&[u8]] = &[b"credential_collection", &collection_bump];
+    let config_seeds: &[&[u8]] = &[b"config", &config_bump];
+
+    let space = ExtensionType::try_calculate_account_len::<Mint>(&[
+        ExtensionType::GroupPointer,
+        ExtensionType::MetadataPointer,
+    ])?;
+
+    let group_space = ExtensionType::try_calculate_account_len::<Mint>(&[
+        ExtensionType::GroupPointer,
+        ExtensionType::MetadataPointer,
+        ExtensionType::TokenGroup,
+    ])?;
+
+    let metadata = TokenMetadata {
+        mint: collection_key,
+        name: NAME.to_string(),
+        symbol: SYMBOL.to_string(),
+        ..Default::default()
+    };
+
+    let lamports = Rent::get()?.minimum_balance(group_space + metadata.tlv_size_of()?);
+
+    create_pda_account(
+        &ctx.accounts.authority.to_account_info(),
+        &collection.to_account_info(),
+        &ctx.accounts.system_program.to_account_info(),
+        collection_seeds,
+        space,
+        lamports,
+        &token_2022::ID,
+    )?;
+
+    invoke(
+        &group_pointer::instruction::initialize(
+            &token_2022::ID,
+            &collection_key,
+            None,
+            Some(collection_key),
+        )?,
+        &[collection.to_account_info()],
+    )?;
+
+    invoke(
+        &metadata_pointer::instruction::initialize(
+            &token_2022::ID,
+            &collection_key,
+            None,
+            Some(collection_key),
+        )?,
+        &[collection.to_account_info()],
+    )?;
+
+    token_2022::initialize_mint2(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            InitializeMint2 {
+                mint: collection.to_account_info(),
+            },
+        ),
+        0,
+        &config_key,
+        None,
+    )?;
+
+    invoke_signed(
+        &metadata_instruction::initialize(
+            &token_2022::ID,
+            &collection_key,
+            &config_key,
+            &collection_key,
+            &config_key,
+            NAME.to_string(),
+            SYMBOL.to_string(),
+            String::new(),
+        ),
+        &[collection.to_account_info(), config.to_account_info()],
+        &[config_seeds],
+    )?;
+
+    invoke_signed(
+        &group_instruction::initialize_group(
+            &token_2022::ID,
+            &collection_key,
+            &collection_key,
+            &config_key,
+            Some(config_key),
+            u32::MAX,
+        ),
+        &[collection.to_account_info(), config.to_account_info()],
+        &[config_seeds],
+    )?;
+
+    Ok(())
+}
@@ -1,19 +1,30 @@
-use anchor_lang::{prelude::*, solana_program::clock};
+use anchor_lang::{prelude::*, solana_program::clock, system_program};
 use anchor_spl::{
     associated_token::AssociatedToken,
-    token::{Mint, Token, TokenAccount, Transfer},
+    token_interface::{Mint, TokenAccount, TokenInterface, TransferChecked},
 };
 
 use crate::{
-    states::{Config, Deal, Gig},
+    events::DealCreated,
+    states::{
+        Config, Deal, DealState, Gig, GigState, Milestone, MintConfig, Referrer, MAX_MILESTONES,
+    },
+    utils::amount_with_fee,
     CustomError,
 };
 
+#[derive(AnchorDeserialize, AnchorSerialize)]
+pub struct MilestoneParams {
+    pub amount: u64,
+    pub due_date: i64,
+}
+
 #[derive(AnchorDeserialize, AnchorSerialize)]
 pub struct CreateDealParams {
-    pub referrer: Option<Pubkey>,
     pub offer: u64,
     pub deadline: i64,
+    /// Leave empty to pay the whole offer on completion. Amounts must add up to the offer.
+    pub milestones: Vec<MilestoneParams>,
 }
 
 #[derive(Accounts)]
@@ -26,114 +37,230 @@ pub struct CreateDeal<'info> {
             b"deal",
             owner.key().as_ref(),
             gig.key().as_ref(),
+            &id.key().to_bytes()[..8],
         ],
         bump,
         space = Deal::len()
     )]
     pub deal: Box<Account<'info, Deal>>,
 
+    #[account(
+        seeds = [
+            b"receipt",
+            deal.key().as_ref(),
+        ],
+        bump,
+        constraint = receipt.data_is_empty() @ CustomError::DealAddressUsed,
+    )]
+    /// CHECK: A receipt left by an earlier deal at this address would be taken for this deal's.
+    pub receipt: UncheckedAccount<'info>,
+
     #[account(
         init,
         payer = owner,
         associated_token::mint = mint,
         associated_token::authority = deal,
+        associated_token::token_program = token_program,
     )]
-    pub escrow: Box<Account<'info, TokenAccount>>,
+    pub escrow: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
 
     #[account(
         mut,
         associated_token::mint = mint,
         associated_token::authority = owner,
+        associated_token::token_program = token_program,
     )]
-    pub owner_wallet: Box<Account<'info, TokenAccount>>,
+    pub owner_wallet: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
 
-    #[account(
-        // TODO: will fail for native token
-        // Solution: possibly put every account involved to optional
-        constraint = mint.key() == gig.mint.unwrap().key(),
-    )]
-    pub mint: Box<Account<'info, Mint>>,
+    // Omitted if the gig is paid in SOL, the deal account will hold the escrow instead.
+    pub mint: Option<Box<InterfaceAccount<'info, Mint>>>,
 
     #[account(
         mut,
-        constraint = gig.state == 1,
+        constraint = gig.state == GigState::Published @ CustomError::InvalidGigState,
+        constraint = gig.mint == mint.as_ref().map(|mint| mint.key()) @ CustomError::MintMismatch,
         constraint = gig.asking <= params.offer @ CustomError::InsufficientOffer,
+        constraint = gig.owner != owner.key() @ CustomError::SelfDeal,
     )]
     pub gig: Box<Account<'info, Gig>>,
 
+    #[account(
+        seeds = [
+            b"mint",
+            mint_config.mint.as_ref(),
+        ],
+        bump = mint_config.bump,
+        constraint = mint_config.mint == gig.payment_mint() @ CustomError::MintNotListed,
+        constraint = mint_config.enabled @ CustomError::MintDisabled,
+    )]
+    pub mint_config: Box<Account<'info, MintConfig>>,
+
+    // Omitted if the deal was not referred.
+    #[account(
+        mut,
+        seeds = [
+            b"referrer",
+            referrer.owner.as_ref(),
+        ],
+        bump = referrer.bump,
+        constraint = gig.accepts_referrals @ CustomError::ReferralsDisabled,
+        constraint = referrer.owner != owner.key() && referrer.owner != gig.owner @ CustomError::InvalidReferrer,
+    )]
+    pub referrer: Option<Box<Account<'info, Referrer>>>,
+
     #[account(mut)]
     pub owner: Signer<'info>,
 
+    pub id: Signer<'info>,
+
     #[account(
         seeds = [b"config"],
         bump = config.bump,
+        constraint = config.paused & 2 != 2 @ CustomError::Paused,
     )]
     pub config: Box<Account<'info, Config>>,
 
     pub system_program: Program<'info, System>,
-    pub token_program: Program<'info, Token>,
+    pub token_program: Interface<'info, TokenInterface>,
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub rent: Sysvar<'info, Rent>,
 }
 
 pub fn create_deal_handler(ctx: Context<CreateDeal>, params: CreateDealParams) -> Result<()> {
     let deal = &mut ctx.accounts.deal;
-    let escrow = &mut ctx.accounts.escrow;
-    let owner_wallet = &mut ctx.accounts.owner_wallet;
+    let escrow = &ctx.accounts.escrow;
+    let owner_wallet = &ctx.accounts.owner_wallet;
+    let mint = &ctx.accounts.mint;
     let gig = &ctx.accounts.gig;
     let config = &ctx.accounts.config;
     let client = &ctx.accounts.owner;
     let clock = clock::Clock::get()?;
 
-    let client_fee_percentage: u64 = config.client_fee_percentage.try_into().unwrap();
+    let mint_config = &ctx.accounts.mint_config;
+
+    // The configs may have changed since the mint was listed.
+    mint_config.validate_fees(config)?;
 
-    let client_fee = client_fee_percentage
-        .checked_mul(params.offer)
-        .unwrap()
-        .checked_div(100_00)
-        .unwrap();
+    deal.client_fee_percentage = mint_config.client_fee_percentage(config);
+    deal.freelancer_fee_percentage = mint_config.freelancer_fee_percentage(config);
+    deal.referral_fee_percentage = mint_config.referral_fee_percentage(config);
+    deal.review_window = config.review_window;
 
-    // TODO: client_fee_min is assumed to be USDC at the moment, possible source of bug
-    let client_fee = if client_fee < config.client_fee_min {
-        config.client_fee_min
-    } else {
-        client_fee
-    };
+    let client_fee = mint_config.client_fee(deal.client_fee_percentage, params.offer)?;
 
-    let total_escrow_amount = params.offer + client_fee;
+    let total_escrow_amount = params
+        .offer
+        .checked_add(client_fee)
+        .ok_or(error!(CustomError::MathOverflow))?;
 
-    let transfer_ix = Transfer {
-        from: owner_wallet.to_account_info(),
-        to: escrow.to_account_info(),
-        authority: client.to_account_info(),
-    };
+    match (&gig.mint, escrow, owner_wallet, mint) {
+        (Some(_), Some(escrow), Some(owner_wallet), Some(mint)) => {
+            // Payouts are computed from what the escrow received, so the client covers the
+            // transfer fee of mints that withhold one.
+            let amount = amount_with_fee(&mint.to_account_info(), total_escrow_amount)?;
 
-    let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), transfer_ix);
+            let transfer_ix = TransferChecked {
+                from: owner_wallet.to_account_info(),
+                mint: mint.to_account_info(),
+                to: escrow.to_account_info(),
+                authority: client.to_account_info(),
+            };
+
+            let cpi_ctx =
+                CpiContext::new(ctx.accounts.token_program.to_account_info(), transfer_ix);
+
+            anchor_spl::token_interface::transfer_checked(cpi_ctx, amount, mint.decimals)?;
+        }
+        (None, _, _, _) => {
+            // SOL is escrowed in the deal account itself, on top of its rent.
+            let transfer_ix = system_program::Transfer {
+                from: client.to_account_info(),
+                to: deal.to_account_info(),
+            };
 
-    anchor_spl::token::transfer(cpi_ctx, u64::from(total_escrow_amount))?;
+            let cpi_ctx =
+                CpiContext::new(ctx.accounts.system_program.to_account_info(), transfer_ix);
 
-    deal.bump = *ctx.bumps.get("deal").unwrap();
+            system_program::transfer(cpi_ctx, total_escrow_amount)?;
+        }
+        _ => return Err(error!(CustomError::MissingTokenAccount)),
+    }
+
+    deal.bump = ctx.bumps.deal;
+    deal.nonce = ctx.accounts.id.key().to_bytes()[..8].try_into().unwrap();
     deal.offer = params.offer;
-    deal.state = 1;
+    deal.state = DealState::Open;
     deal.gig = gig.key();
     deal.freelancer = gig.owner.key();
     deal.client = client.key();
     deal.time_created = clock.unix_timestamp;
     deal.deadline = params.deadline;
 
-    // TODO: referrer could be the client itself, exploiting the pay
-    match params.referrer {
-        Some(referrer) => {
-            if client.key() != referrer {
-                deal.referrer = Some(referrer);
-            }
-        }
-        None => (),
+    if let Some(referrer) = &mut ctx.accounts.referrer {
+        referrer.referrals = referrer
+            .referrals
+            .checked_add(1)
+            .ok_or(error!(CustomError::MathOverflow))?;
+        deal.referrer = Some(referrer.owner);
     }
 
-    if deal.time_created + gig.min_completion_time > params.deadline {
+    let min_deadline = deal
+        .time_created
+        .checked_add(gig.min_completion_time)
+        .ok_or(error!(CustomError::MathOverflow))?;
+
+    if min_deadline > params.deadline {
         return Err(error!(CustomError::DeadlineTooShort));
     }
 
+    if params.milestones.len() > MAX_MILESTONES {
+        return Err(error!(CustomError::InvalidMilestones));
+    }
+
+    if !params.milestones.is_empty() {
+        let total = params
+            .milestones
+            .iter()
+            .try_fold(0u64, |total, milestone| total.checked_add(milestone.amount))
+            .ok_or(error!(CustomError::MathOverflow))?;
+
+        let is_ordered = params
+            .milestones
+            .windows(2)
+            .all(|pair| pair[0].due_date <= pair[1].due_date);
+
+        let first_due_date = params.milestones[0].due_date;
+        let last_due_date = params.milestones[params.milestones.len() - 1].due_date;
+
+        if total != params.offer
+            || !is_ordered
+            || first_due_date <= deal.time_created
+            || last_due_date > params.deadline
+        {
+            return Err(error!(CustomError::InvalidMilestones));
+        }
+    }
+
+    deal.milestones = params
+        .milestones
+        .iter()
+        .map(|milestone| Milestone {
+            amount: milestone.amount,
+            due_date: milestone.due_date,
+            released: false,
+        })
+        .collect();
+
+    emit!(DealCreated {
+        deal: deal.key(),
+        gig: gig.key(),
+        client: client.key(),
+        freelancer: deal.freelancer,
+        offer: deal.offer,
+        deadline: deal.deadline,
+        client_fee,
+        referrer: deal.referrer,
+    });
+
     Ok(())
 }
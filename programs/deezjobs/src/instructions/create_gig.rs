@@ -1,6 +1,10 @@
-use crate::states::Gig;
+use crate::{
+    events::GigCreated,
+    states::{Config, Gig, GigState},
+    CustomError,
+};
 use anchor_lang::prelude::*;
-use anchor_spl::token::Mint;
+use anchor_spl::token_interface::Mint;
 
 #[derive(AnchorDeserialize, AnchorSerialize)]
 pub struct CreateGigParams {
@@ -8,6 +12,8 @@ pub struct CreateGigParams {
     pub skills: u64,
     pub asking: u64,
     pub min_completion_time: i64,
+    pub is_published: bool,
+    pub accepts_referrals: bool,
 }
 
 #[derive(Accounts)]
@@ -34,28 +40,45 @@ pub struct CreateGig<'info> {
 
     pub id: Signer<'info>,
 
-    pub mint: Option<Account<'info, Mint>>,
+    pub mint: Option<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = config.paused & 1 != 1 @ CustomError::Paused,
+    )]
+    pub config: Account<'info, Config>,
 
     pub system_program: Program<'info, System>,
 }
 
 pub fn create_gig_handler(ctx: Context<CreateGig>, params: CreateGigParams) -> Result<()> {
     let gig = &mut ctx.accounts.gig;
-    gig.bump = *ctx.bumps.get("gig").unwrap();
+    gig.bump = ctx.bumps.gig;
     gig.nonce = ctx.accounts.id.key().to_bytes()[..8].try_into().unwrap();
     gig.payer = ctx.accounts.payer.key();
     gig.owner = ctx.accounts.owner.key();
-    gig.mint = match &ctx.accounts.mint {
-        Some(mint) => Some(mint.key()),
-        None => None,
-    };
+    gig.mint = ctx.accounts.mint.as_ref().map(|mint| mint.key());
 
-    gig.state = 1; // if params.is_published { 1 } else { 0 };
+    gig.state = if params.is_published {
+        GigState::Published
+    } else {
+        GigState::Draft
+    };
     gig.pending_deals = 0;
     gig.category = params.category;
     gig.skills = params.skills;
     gig.asking = params.asking;
     gig.min_completion_time = params.min_completion_time;
+    gig.accepts_referrals = params.accepts_referrals;
+
+    emit!(GigCreated {
+        gig: gig.key(),
+        owner: gig.owner,
+        mint: gig.mint,
+        asking: gig.asking,
+        state: gig.state,
+    });
 
     Ok(())
 }
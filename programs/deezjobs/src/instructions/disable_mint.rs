@@ -0,0 +1,9 @@
+use crate::instructions::UpdateMint;
+use anchor_lang::prelude::*;
+
+/// Stops new deals from using the mint. Ongoing deals can still be completed or settled.
+pub fn disable_mint_handler(ctx: Context<UpdateMint>) -> Result<()> {
+    ctx.accounts.mint_config.enabled = false;
+
+    Ok(())
+}
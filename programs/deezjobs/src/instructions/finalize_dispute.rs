@@ -0,0 +1,34 @@
+use anchor_lang::{prelude::*, solana_program::clock};
+
+use crate::{
+    instructions::{settle, SettleDispute},
+    CustomError,
+};
+
+/// Anyone can execute the community's decision once the voting window closes with enough votes.
+/// The client's share is the weighted average of every vote.
+pub fn finalize_dispute_handler(ctx: Context<SettleDispute>) -> Result<()> {
+    let clock = clock::Clock::get()?;
+    let dispute = &ctx.accounts.dispute;
+
+    if clock.unix_timestamp < dispute.voting_ends_at {
+        return Err(error!(CustomError::VotingInProgress));
+    }
+
+    if dispute.total_weight == 0 {
+        return Err(error!(CustomError::NoVotes));
+    }
+
+    if dispute.total_weight < dispute.quorum {
+        return Err(error!(CustomError::QuorumNotReached));
+    }
+
+    let client_share: u16 = dispute
+        .weighted_client_share
+        .checked_div(dispute.total_weight.into())
+        .ok_or(error!(CustomError::MathOverflow))?
+        .try_into()
+        .map_err(|_| error!(CustomError::InvalidShare))?;
+
+    settle(ctx, client_share)
+}
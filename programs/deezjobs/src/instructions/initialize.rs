@@ -1,13 +1,17 @@
-use crate::{program::Deezjobs, states::Config};
+use crate::{program::Deezjobs, states::Config, CustomError};
 use anchor_lang::prelude::*;
 use anchor_spl::{token::{Token, TokenAccount, Mint}, associated_token::AssociatedToken};
 
 #[derive(AnchorDeserialize, AnchorSerialize)]
 pub struct InitializeParams {
+    pub arbiter: Pubkey,
     pub client_fee_percentage: u16,
-    pub client_fee_min: u64, // USDC
     pub freelancer_fee_percentage: u16,
     pub referral_fee_percentage: u16,
+    pub governance_mint: Pubkey,
+    pub dispute_vote_duration: i64,
+    pub dispute_quorum: u64,
+    pub review_window: i64,
 }
 
 #[derive(Accounts)]
@@ -37,12 +41,12 @@ pub struct Initialize<'info> {
     pub mint: Account<'info, Mint>,
 
     #[account(
-        constraint = program.programdata_address()? == Some(program_data.key())
+        constraint = program.programdata_address()? == Some(program_data.key()) @ CustomError::InvalidProgramData,
     )]
     pub program: Program<'info, Deezjobs>,
 
     #[account(
-        constraint = program_data.upgrade_authority_address == Some(upgrade_authority.key())
+        constraint = program_data.upgrade_authority_address == Some(upgrade_authority.key()) @ CustomError::Unauthorized,
     )]
     pub program_data: Account<'info, ProgramData>,
 
@@ -57,14 +61,22 @@ pub struct Initialize<'info> {
 
 pub fn initialize_handler(ctx: Context<Initialize>, params: InitializeParams) -> Result<()> {
     let config = &mut ctx.accounts.config;
-    config.bump = *ctx.bumps.get("config").unwrap();
+    config.bump = ctx.bumps.config;
 
     config.authority = ctx.accounts.upgrade_authority.key();
+    config.pending_authority = None;
+    config.arbiter = params.arbiter;
     config.treasury = ctx.accounts.treasury.key();
     config.client_fee_percentage = params.client_fee_percentage;
-    config.client_fee_min = params.client_fee_min;
     config.freelancer_fee_percentage = params.freelancer_fee_percentage;
     config.referral_fee_percentage = params.referral_fee_percentage;
+    config.governance_mint = params.governance_mint;
+    config.dispute_vote_duration = params.dispute_vote_duration;
+    config.dispute_quorum = params.dispute_quorum;
+    config.review_window = params.review_window;
+    config.paused = 0;
 
-    Ok(())
+    config.validate_fees()?;
+    config.validate_disputes()?;
+    config.validate_review_window()
 }
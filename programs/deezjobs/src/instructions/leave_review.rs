@@ -0,0 +1,188 @@
+use anchor_lang::{prelude::*, solana_program::clock};
+use anchor_spl::{
+    associated_token::{get_associated_token_address_with_program_id, AssociatedToken},
+    token_2022::{self, Token2022},
+};
+
+use crate::{
+    credential::{self, Credential, CredentialAuthority},
+    events::ReviewLeft,
+    states::{Config, Gig, Profile, Receipt, Review},
+    CustomError,
+};
+
+#[derive(AnchorDeserialize, AnchorSerialize)]
+pub struct LeaveReviewParams {
+    /// Score from 1 to 5.
+    pub score: u8,
+
+    /// Hash of the review content which is stored off-chain.
+    pub content: [u8; 32],
+}
+
+#[derive(Accounts)]
+pub struct LeaveReview<'info> {
+    #[account(
+        seeds = [
+            b"receipt",
+            receipt.deal.as_ref(),
+        ],
+        bump = receipt.bump,
+        constraint = receipt.time_completed > 0 @ CustomError::DealNotCompleted,
+    )]
+    pub receipt: Box<Account<'info, Receipt>>,
+
+    #[account(
+        init,
+        payer = author,
+        seeds = [
+            b"review",
+            receipt.deal.as_ref(),
+            author.key().as_ref(),
+        ],
+        bump,
+        space = Review::len()
+    )]
+    pub review: Box<Account<'info, Review>>,
+
+    #[account(
+        mut,
+        address = receipt.gig @ CustomError::GigMismatch,
+    )]
+    /// CHECK: The gig may have been closed since, its rating is only kept while it exists.
+    pub gig: UncheckedAccount<'info>,
+
+    /// CHECK: Checked against the receipt in the handler.
+    pub subject: UncheckedAccount<'info>,
+
+    #[account(
+        init_if_needed,
+        payer = author,
+        seeds = [
+            b"profile",
+            subject.key().as_ref(),
+        ],
+        bump,
+        space = Profile::len()
+    )]
+    pub subject_profile: Box<Account<'info, Profile>>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"credential",
+            subject.key().as_ref(),
+        ],
+        bump,
+    )]
+    /// CHECK: See `Credential`.
+    pub subject_credential: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        address = get_associated_token_address_with_program_id(
+            &subject.key(),
+            &subject_credential.key(),
+            &token_2022::ID,
+        ) @ CustomError::CredentialMismatch,
+    )]
+    /// CHECK: See `Credential`.
+    pub subject_credential_account: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"credential_collection"],
+        bump,
+    )]
+    /// CHECK: See `CredentialAuthority`.
+    pub credential_collection: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Box<Account<'info, Config>>,
+
+    #[account(mut)]
+    pub author: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+    pub token_2022_program: Program<'info, Token2022>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
+
+/// Both parties of a completed deal can review each other once. Scores left by the client also count
+/// towards the gig's rating.
+pub fn leave_review_handler(ctx: Context<LeaveReview>, params: LeaveReviewParams) -> Result<()> {
+    let clock = clock::Clock::get()?;
+    let receipt = &ctx.accounts.receipt;
+    let author = ctx.accounts.author.key();
+    let subject = ctx.accounts.subject.key();
+
+    if author == subject {
+        return Err(error!(CustomError::SelfReview));
+    }
+
+    let by_client = author == receipt.client && subject == receipt.freelancer;
+    let by_freelancer = author == receipt.freelancer && subject == receipt.client;
+
+    if !by_client && !by_freelancer {
+        return Err(error!(CustomError::Unauthorized));
+    }
+
+    if !(1..=5).contains(&params.score) {
+        return Err(error!(CustomError::InvalidScore));
+    }
+
+    let review = &mut ctx.accounts.review;
+    review.bump = ctx.bumps.review;
+    review.deal = receipt.deal;
+    review.gig = receipt.gig;
+    review.author = author;
+    review.subject = subject;
+    review.score = params.score;
+    review.content = params.content;
+    review.time_created = clock.unix_timestamp;
+
+    let gig_info = ctx.accounts.gig.to_account_info();
+
+    if by_client && !gig_info.data_is_empty() {
+        let mut gig = Gig::try_deserialize(&mut &gig_info.try_borrow_data()?[..])?;
+        gig.record_rating(params.score)?;
+        gig.try_serialize(&mut &mut gig_info.try_borrow_mut_data()?[..])?;
+    }
+
+    let subject_profile = &mut ctx.accounts.subject_profile;
+    subject_profile.register(ctx.bumps.subject_profile, subject);
+    subject_profile.record_rating(params.score)?;
+
+    let config = &ctx.accounts.config;
+
+    let authority = CredentialAuthority {
+        config: config.to_account_info(),
+        config_bump: config.bump,
+        collection: ctx.accounts.credential_collection.to_account_info(),
+        payer: ctx.accounts.author.to_account_info(),
+        token_program: ctx.accounts.token_2022_program.to_account_info(),
+        associated_token_program: ctx.accounts.associated_token_program.to_account_info(),
+        system_program: ctx.accounts.system_program.to_account_info(),
+    };
+
+    Credential {
+        holder: ctx.accounts.subject.to_account_info(),
+        mint: ctx.accounts.subject_credential.to_account_info(),
+        mint_bump: ctx.bumps.subject_credential,
+        token_account: ctx.accounts.subject_credential_account.to_account_info(),
+    }
+    .sync(&authority, subject_profile, &[credential::RATING])?;
+
+    emit!(ReviewLeft {
+        review: review.key(),
+        deal: review.deal,
+        author,
+        subject,
+        score: review.score,
+    });
+
+    Ok(())
+}
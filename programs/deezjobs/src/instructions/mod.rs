@@ -1,15 +1,65 @@
+mod accept_authority;
+mod accept_counter;
 mod accept_deal;
+mod add_mint;
+mod auto_complete;
+mod cast_vote;
+mod claim_expired;
 mod close_deal;
 mod close_gig;
 mod complete_deal;
+mod counter_offer;
+mod create_collection;
 mod create_deal;
 mod create_gig;
+mod disable_mint;
+mod finalize_dispute;
 mod initialize;
+mod leave_review;
+mod propose_authority;
+mod raise_dispute;
+mod register_referrer;
+mod reject_deal;
+mod release_milestone;
+mod revise_offer;
+mod set_gig_state;
+mod set_pause;
+mod settle_dispute;
+mod submit_delivery;
+mod update_config;
+mod update_gig;
+mod update_mint;
+mod withdraw_vote;
 
+pub use accept_authority::*;
+pub use accept_counter::*;
 pub use accept_deal::*;
+pub use add_mint::*;
+pub use auto_complete::*;
+pub use cast_vote::*;
+pub use claim_expired::*;
 pub use close_deal::*;
 pub use close_gig::*;
 pub use complete_deal::*;
+pub use counter_offer::*;
+pub use create_collection::*;
 pub use create_deal::*;
 pub use create_gig::*;
+pub use disable_mint::*;
+pub use finalize_dispute::*;
 pub use initialize::*;
+pub use leave_review::*;
+pub use propose_authority::*;
+pub use raise_dispute::*;
+pub use register_referrer::*;
+pub use reject_deal::*;
+pub use release_milestone::*;
+pub use revise_offer::*;
+pub use set_gig_state::*;
+pub use set_pause::*;
+pub use settle_dispute::*;
+pub use submit_delivery::*;
+pub use update_config::*;
+pub use update_gig::*;
+pub use update_mint::*;
+pub use withdraw_vote::*;
@@ -0,0 +1,27 @@
+use crate::{states::Config, CustomError};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct ProposeAuthority<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, Config>,
+
+    /// CHECK: can be any account, eg. a multisig. It has to sign `accept_authority` to take over.
+    pub new_authority: UncheckedAccount<'info>,
+
+    #[account(
+        constraint = authority.key() == config.authority.key() @ CustomError::Unauthorized,
+    )]
+    pub authority: Signer<'info>,
+}
+
+pub fn propose_authority_handler(ctx: Context<ProposeAuthority>) -> Result<()> {
+    let config = &mut ctx.accounts.config;
+    config.pending_authority = Some(ctx.accounts.new_authority.key());
+
+    Ok(())
+}
@@ -0,0 +1,78 @@
+use anchor_lang::{prelude::*, solana_program::clock};
+
+use crate::{
+    events::DisputeRaised,
+    states::{Config, Deal, DealState, Dispute},
+    CustomError,
+};
+
+#[derive(AnchorDeserialize, AnchorSerialize)]
+pub struct RaiseDisputeParams {
+    pub reason: u8,
+    pub evidence: [u8; 32],
+}
+
+#[derive(Accounts)]
+#[instruction(params: RaiseDisputeParams)]
+pub struct RaiseDispute<'info> {
+    #[account(
+        init,
+        payer = signer,
+        seeds = [
+            b"dispute",
+            deal.key().as_ref(),
+        ],
+        bump,
+        space = Dispute::len()
+    )]
+    pub dispute: Account<'info, Dispute>,
+
+    #[account(mut)]
+    pub deal: Account<'info, Deal>,
+
+    #[account(
+        mut,
+        constraint = signer.key() == deal.client.key() || signer.key() == deal.freelancer.key() @ CustomError::Unauthorized,
+    )]
+    pub signer: Signer<'info>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, Config>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn raise_dispute_handler(ctx: Context<RaiseDispute>, params: RaiseDisputeParams) -> Result<()> {
+    let clock = clock::Clock::get()?;
+    let dispute = &mut ctx.accounts.dispute;
+    let deal = &mut ctx.accounts.deal;
+
+    dispute.bump = ctx.bumps.dispute;
+    dispute.deal = deal.key();
+    dispute.opened_by = ctx.accounts.signer.key();
+    dispute.reason = params.reason;
+    dispute.evidence = params.evidence;
+    dispute.time_opened = clock.unix_timestamp;
+    dispute.voting_ends_at = clock
+        .unix_timestamp
+        .checked_add(ctx.accounts.config.dispute_vote_duration)
+        .ok_or(error!(CustomError::MathOverflow))?;
+    dispute.governance_mint = ctx.accounts.config.governance_mint;
+    dispute.quorum = ctx.accounts.config.dispute_quorum;
+    dispute.total_weight = 0;
+    dispute.weighted_client_share = 0;
+
+    deal.transition(DealState::Disputed)?;
+
+    emit!(DisputeRaised {
+        dispute: dispute.key(),
+        deal: deal.key(),
+        opened_by: dispute.opened_by,
+        reason: dispute.reason,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,40 @@
+use anchor_lang::{prelude::*, solana_program::clock};
+
+use crate::{events::ReferrerRegistered, states::Referrer};
+
+#[derive(Accounts)]
+pub struct RegisterReferrer<'info> {
+    #[account(
+        init,
+        payer = owner,
+        seeds = [
+            b"referrer",
+            owner.key().as_ref(),
+        ],
+        bump,
+        space = Referrer::len()
+    )]
+    pub referrer: Account<'info, Referrer>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Only registered referrers can be set on a deal.
+pub fn register_referrer_handler(ctx: Context<RegisterReferrer>) -> Result<()> {
+    let clock = clock::Clock::get()?;
+    let referrer = &mut ctx.accounts.referrer;
+    referrer.bump = ctx.bumps.referrer;
+    referrer.owner = ctx.accounts.owner.key();
+    referrer.referrals = 0;
+    referrer.time_registered = clock.unix_timestamp;
+
+    emit!(ReferrerRegistered {
+        referrer: referrer.key(),
+        owner: referrer.owner,
+    });
+
+    Ok(())
+}
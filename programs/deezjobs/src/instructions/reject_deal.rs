@@ -0,0 +1,115 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{Mint, TokenAccount, TokenInterface},
+};
+
+use crate::{
+    events::DealRejected,
+    states::{Deal, DealState, Gig},
+    utils::{close_program_account, refund_escrow},
+    CustomError,
+};
+
+#[derive(AnchorDeserialize, AnchorSerialize)]
+pub struct RejectDealParams {
+    pub reason: u8,
+}
+
+#[derive(Accounts)]
+#[instruction(params: RejectDealParams)]
+pub struct RejectDeal<'info> {
+    #[account(
+        mut,
+        constraint = deal.gig.key() == gig.key() @ CustomError::GigMismatch,
+    )]
+    pub deal: Account<'info, Deal>,
+
+    #[account(
+        constraint = gig.owner.key() == freelancer.key() @ CustomError::Unauthorized,
+        constraint = gig.mint == mint.as_ref().map(|mint| mint.key()) @ CustomError::MintMismatch,
+    )]
+    pub gig: Account<'info, Gig>,
+
+    #[account(
+        mut,
+        constraint = client.key() == deal.client.key() @ CustomError::ClientMismatch,
+    )]
+    /// CHECK: should be the owner of the deal
+    pub client: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = deal,
+        associated_token::token_program = token_program,
+    )]
+    pub escrow: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = client,
+        associated_token::token_program = token_program,
+    )]
+    pub client_wallet: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    // Omitted if the gig is paid in SOL.
+    #[account(mut)]
+    pub mint: Option<InterfaceAccount<'info, Mint>>,
+
+    pub freelancer: Signer<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
+
+pub fn reject_deal_handler(ctx: Context<RejectDeal>, params: RejectDealParams) -> Result<()> {
+    let client = &mut ctx.accounts.client;
+    let deal = &mut ctx.accounts.deal;
+    let gig = &ctx.accounts.gig;
+
+    deal.transition(DealState::Cancelled)?;
+
+    // Refund the escrow to the client
+    // SOL escrow is refunded as soon as the deal account is closed.
+
+    if gig.mint.is_some() {
+        let escrow = ctx
+            .accounts
+            .escrow
+            .as_ref()
+            .ok_or(error!(CustomError::MissingTokenAccount))?;
+
+        let mint = ctx
+            .accounts
+            .mint
+            .as_ref()
+            .ok_or(error!(CustomError::MintMismatch))?;
+
+        let client_wallet = ctx
+            .accounts
+            .client_wallet
+            .as_ref()
+            .ok_or(error!(CustomError::MissingTokenAccount))?;
+
+        refund_escrow(
+            deal,
+            escrow,
+            mint,
+            client_wallet,
+            &client.to_account_info(),
+            &ctx.accounts.token_program.to_account_info(),
+        )?;
+    }
+
+    emit!(DealRejected {
+        deal: deal.key(),
+        gig: gig.key(),
+        client: client.key(),
+        freelancer: deal.freelancer,
+        reason: params.reason,
+    });
+
+    close_program_account(deal, &client.to_account_info())
+}
@@ -0,0 +1,33 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    instructions::{release, require_client_or_authority, CompleteDeal},
+    CustomError,
+};
+
+#[derive(AnchorDeserialize, AnchorSerialize)]
+pub struct ReleaseMilestoneParams {
+    pub index: u8,
+}
+
+/// Pays out a single milestone, releasing the last one completes the deal.
+pub fn release_milestone_handler(
+    ctx: Context<CompleteDeal>,
+    params: ReleaseMilestoneParams,
+) -> Result<()> {
+    require_client_or_authority(&ctx)?;
+
+    let deal = &mut ctx.accounts.deal;
+
+    let milestone = deal
+        .milestones
+        .get_mut(usize::from(params.index))
+        .filter(|milestone| !milestone.released)
+        .ok_or(error!(CustomError::InvalidMilestone))?;
+
+    milestone.released = true;
+
+    let amount = milestone.amount;
+
+    release(ctx, amount)
+}
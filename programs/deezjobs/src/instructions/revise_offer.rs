@@ -0,0 +1,33 @@
+use anchor_lang::{prelude::*, solana_program::clock};
+
+use crate::{
+    instructions::{apply_offer, UpdateOffer},
+    CustomError,
+};
+
+#[derive(AnchorDeserialize, AnchorSerialize)]
+pub struct ReviseOfferParams {
+    pub offer: u64,
+    pub deadline: i64,
+}
+
+/// The client's answer to a counter offer, the new terms are escrowed right away.
+pub fn revise_offer_handler(ctx: Context<UpdateOffer>, params: ReviseOfferParams) -> Result<()> {
+    let clock = clock::Clock::get()?;
+    let gig = &ctx.accounts.gig;
+
+    if gig.asking > params.offer {
+        return Err(error!(CustomError::InsufficientOffer));
+    }
+
+    let min_deadline = clock
+        .unix_timestamp
+        .checked_add(gig.min_completion_time)
+        .ok_or(error!(CustomError::MathOverflow))?;
+
+    if min_deadline > params.deadline {
+        return Err(error!(CustomError::DeadlineTooShort));
+    }
+
+    apply_offer(ctx, params.offer, params.deadline)
+}
@@ -0,0 +1,35 @@
+use crate::{
+    events::GigStateChanged,
+    states::{Gig, GigState},
+    CustomError,
+};
+use anchor_lang::prelude::*;
+
+#[derive(AnchorDeserialize, AnchorSerialize)]
+pub struct SetGigStateParams {
+    pub state: GigState,
+}
+
+#[derive(Accounts)]
+#[instruction(params: SetGigStateParams)]
+pub struct SetGigState<'info> {
+    #[account(
+        mut,
+        constraint = gig.owner.key() == owner.key() @ CustomError::Unauthorized,
+    )]
+    pub gig: Account<'info, Gig>,
+
+    pub owner: Signer<'info>,
+}
+
+pub fn set_gig_state_handler(ctx: Context<SetGigState>, params: SetGigStateParams) -> Result<()> {
+    let gig = &mut ctx.accounts.gig;
+    gig.transition(params.state)?;
+
+    emit!(GigStateChanged {
+        gig: gig.key(),
+        state: gig.state,
+    });
+
+    Ok(())
+}
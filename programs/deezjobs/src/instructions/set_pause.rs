@@ -0,0 +1,30 @@
+use crate::{states::Config, CustomError};
+use anchor_lang::prelude::*;
+
+#[derive(AnchorDeserialize, AnchorSerialize)]
+pub struct SetPauseParams {
+    pub paused: u8,
+}
+
+#[derive(Accounts)]
+#[instruction(params: SetPauseParams)]
+pub struct SetPause<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        constraint = authority.key() == config.authority.key() @ CustomError::Unauthorized,
+    )]
+    pub authority: Signer<'info>,
+}
+
+pub fn set_pause_handler(ctx: Context<SetPause>, params: SetPauseParams) -> Result<()> {
+    let config = &mut ctx.accounts.config;
+    config.paused = params.paused;
+
+    Ok(())
+}
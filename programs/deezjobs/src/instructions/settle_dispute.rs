@@ -1,2 +1,498 @@
-// deal.state & 4 == 4
-// config.authority (community multisig) to resolve the dispute
+use std::cmp::Ordering;
+
+use anchor_lang::{prelude::*, solana_program::clock};
+use anchor_spl::{
+    associated_token::{get_associated_token_address_with_program_id, AssociatedToken},
+    token_2022::{self, Token2022},
+    token_interface::{Mint, TokenAccount, TokenInterface, TransferChecked},
+};
+
+use crate::{
+    credential::{self, Credential, CredentialAuthority},
+    events::DisputeSettled,
+    states::{Config, Deal, DealState, Dispute, Gig, Profile},
+    utils::{
+        close_escrow, close_program_account, escrowed_lamports, transfer_lamports, withheld_fee,
+    },
+    CustomError,
+};
+
+#[derive(AnchorDeserialize, AnchorSerialize)]
+pub struct SettleDisputeParams {
+    /// Portion of the offer refunded to the client. Percentage with 2 decimal places (0 to 10000).
+    /// The rest of the offer goes to the freelancer.
+    pub client_share: u16,
+}
+
+#[derive(Accounts)]
+pub struct SettleDispute<'info> {
+    #[account(
+        mut,
+        constraint = gig.key() == deal.gig.key() @ CustomError::GigMismatch,
+        constraint = gig.mint == mint.as_ref().map(|mint| mint.key()) @ CustomError::MintMismatch,
+    )]
+    pub gig: Box<Account<'info, Gig>>,
+
+    #[account(mut)]
+    pub deal: Box<Account<'info, Deal>>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"dispute",
+            deal.key().as_ref(),
+        ],
+        bump = dispute.bump,
+    )]
+    pub dispute: Box<Account<'info, Dispute>>,
+
+    #[account(
+        mut,
+        constraint = opened_by.key() == dispute.opened_by.key() @ CustomError::OpenerMismatch,
+    )]
+    /// CHECK: gets back the rent of the dispute
+    pub opened_by: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        constraint = freelancer.key() == deal.freelancer.key() @ CustomError::FreelancerMismatch,
+    )]
+    /// CHECK:
+    pub freelancer: UncheckedAccount<'info>,
+
+    // Both profiles are loaded, the write of one would overwrite the other.
+    #[account(
+        mut,
+        constraint = client.key() == deal.client.key() @ CustomError::ClientMismatch,
+        constraint = client.key() != freelancer.key() @ CustomError::SelfDeal,
+    )]
+    /// CHECK:
+    pub client: UncheckedAccount<'info>,
+
+    #[account(
+        init_if_needed,
+        payer = signer,
+        seeds = [
+            b"profile",
+            freelancer.key().as_ref(),
+        ],
+        bump,
+        space = Profile::len()
+    )]
+    pub freelancer_profile: Box<Account<'info, Profile>>,
+
+    #[account(
+        init_if_needed,
+        payer = signer,
+        seeds = [
+            b"profile",
+            client.key().as_ref(),
+        ],
+        bump,
+        space = Profile::len()
+    )]
+    pub client_profile: Box<Account<'info, Profile>>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"credential",
+            freelancer.key().as_ref(),
+        ],
+        bump,
+    )]
+    /// CHECK: See `Credential`.
+    pub freelancer_credential: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        address = get_associated_token_address_with_program_id(
+            &freelancer.key(),
+            &freelancer_credential.key(),
+            &token_2022::ID,
+        ) @ CustomError::CredentialMismatch,
+    )]
+    /// CHECK: See `Credential`.
+    pub freelancer_credential_account: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"credential",
+            client.key().as_ref(),
+        ],
+        bump,
+    )]
+    /// CHECK: See `Credential`.
+    pub client_credential: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        address = get_associated_token_address_with_program_id(
+            &client.key(),
+            &client_credential.key(),
+            &token_2022::ID,
+        ) @ CustomError::CredentialMismatch,
+    )]
+    /// CHECK: See `Credential`.
+    pub client_credential_account: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"credential_collection"],
+        bump,
+    )]
+    /// CHECK: See `CredentialAuthority`.
+    pub credential_collection: UncheckedAccount<'info>,
+
+    // Omitted along with the token accounts if the gig is paid in SOL.
+    #[account(mut)]
+    pub mint: Option<Box<InterfaceAccount<'info, Mint>>>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = deal,
+        associated_token::token_program = token_program,
+    )]
+    pub escrow: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    #[account(
+        init_if_needed,
+        payer = signer,
+        associated_token::mint = mint,
+        associated_token::authority = client,
+        associated_token::token_program = token_program,
+    )]
+    pub client_token_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    #[account(
+        init_if_needed,
+        payer = signer,
+        associated_token::mint = mint,
+        associated_token::authority = freelancer,
+        associated_token::token_program = token_program,
+    )]
+    pub freelancer_token_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    #[account(
+        mut,
+        constraint = treasury.key() == config.treasury.key() @ CustomError::TreasuryMismatch,
+    )]
+    /// CHECK:
+    pub treasury: UncheckedAccount<'info>,
+
+    #[account(
+        init_if_needed,
+        payer = signer,
+        associated_token::mint = mint,
+        associated_token::authority = treasury,
+        associated_token::token_program = token_program,
+    )]
+    pub treasury_token_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Box<Account<'info, Config>>,
+
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub token_2022_program: Program<'info, Token2022>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+/// The authority or the arbiter can only settle the dispute if the community vote did not reach the quorum.
+pub fn settle_dispute_handler(
+    ctx: Context<SettleDispute>,
+    params: SettleDisputeParams,
+) -> Result<()> {
+    let clock = clock::Clock::get()?;
+    let config = &ctx.accounts.config;
+    let dispute = &ctx.accounts.dispute;
+    let signer = &ctx.accounts.signer;
+
+    if signer.key() != config.authority.key() && signer.key() != config.arbiter.key() {
+        return Err(error!(CustomError::Unauthorized));
+    }
+
+    if clock.unix_timestamp < dispute.voting_ends_at {
+        return Err(error!(CustomError::VotingInProgress));
+    }
+
+    if dispute.total_weight >= dispute.quorum {
+        return Err(error!(CustomError::DisputeHasVotes));
+    }
+
+    if params.client_share > 100_00 {
+        return Err(error!(CustomError::InvalidShare));
+    }
+
+    settle(ctx, params.client_share)
+}
+
+/// Splits the escrow between the client and the freelancer, then closes the escrow, the deal and the dispute.
+pub fn settle(ctx: Context<SettleDispute>, client_share: u16) -> Result<()> {
+    let gig = &mut ctx.accounts.gig;
+    let deal = &mut ctx.accounts.deal;
+    let client = &mut ctx.accounts.client;
+    let config = &ctx.accounts.config;
+
+    let escrow = &ctx.accounts.escrow;
+
+    deal.transition(DealState::Settled)?;
+    gig.pending_deals = gig
+        .pending_deals
+        .checked_sub(1)
+        .ok_or(error!(CustomError::MathOverflow))?;
+
+    // Compute the split.
+
+    let escrow_amount = match escrow {
+        Some(escrow) => escrow.amount,
+        None => escrowed_lamports(&deal.to_account_info())?,
+    };
+
+    // Milestones released before the dispute are not part of the split.
+    let offer = deal.remaining_offer();
+
+    let client_share_percentage: u64 = client_share.into();
+
+    let client_refund = client_share_percentage
+        .checked_mul(offer)
+        .ok_or(error!(CustomError::MathOverflow))?
+        .checked_div(100_00)
+        .ok_or(error!(CustomError::MathOverflow))?;
+
+    let freelancer_share = offer
+        .checked_sub(client_refund)
+        .ok_or(error!(CustomError::MathOverflow))?;
+
+    // Reputation, an even split has no winner.
+
+    let freelancer_profile = &mut ctx.accounts.freelancer_profile;
+    freelancer_profile.register(ctx.bumps.freelancer_profile, deal.freelancer);
+    freelancer_profile.add_volume(gig.payment_mint(), freelancer_share)?;
+
+    let client_profile = &mut ctx.accounts.client_profile;
+    client_profile.register(ctx.bumps.client_profile, deal.client);
+    client_profile.add_volume(gig.payment_mint(), freelancer_share)?;
+
+    match client_share.cmp(&50_00) {
+        Ordering::Greater => {
+            client_profile.record_dispute(true)?;
+            freelancer_profile.record_dispute(false)?;
+        }
+        Ordering::Less => {
+            freelancer_profile.record_dispute(true)?;
+            client_profile.record_dispute(false)?;
+        }
+        Ordering::Equal => (),
+    }
+
+    let authority = CredentialAuthority {
+        config: config.to_account_info(),
+        config_bump: config.bump,
+        collection: ctx.accounts.credential_collection.to_account_info(),
+        payer: ctx.accounts.signer.to_account_info(),
+        token_program: ctx.accounts.token_2022_program.to_account_info(),
+        associated_token_program: ctx.accounts.associated_token_program.to_account_info(),
+        system_program: ctx.accounts.system_program.to_account_info(),
+    };
+
+    let keys = [credential::DISPUTES_WON, credential::DISPUTES_LOST];
+
+    Credential {
+        holder: ctx.accounts.freelancer.to_account_info(),
+        mint: ctx.accounts.freelancer_credential.to_account_info(),
+        mint_bump: ctx.bumps.freelancer_credential,
+        token_account: ctx.accounts.freelancer_credential_account.to_account_info(),
+    }
+    .sync(&authority, freelancer_profile, &keys)?;
+
+    Credential {
+        holder: client.to_account_info(),
+        mint: ctx.accounts.client_credential.to_account_info(),
+        mint_bump: ctx.bumps.client_credential,
+        token_account: ctx.accounts.client_credential_account.to_account_info(),
+    }
+    .sync(&authority, client_profile, &keys)?;
+
+    // Protocol fee is only taken from the freelancer's share, there is no referral bounty for disputed deals.
+
+    let freelancer_fee_percentage: u64 = deal.freelancer_fee_percentage.into();
+
+    let freelancer_fee = freelancer_fee_percentage
+        .checked_mul(freelancer_share)
+        .ok_or(error!(CustomError::MathOverflow))?
+        .checked_div(100_00)
+        .ok_or(error!(CustomError::MathOverflow))?;
+
+    let freelancer_pay = freelancer_share
+        .checked_sub(freelancer_fee)
+        .ok_or(error!(CustomError::MathOverflow))?;
+    let treasury_pay = freelancer_fee;
+
+    // Remainder of the escrow amount, this includes the client's fee which is refunded as well.
+    let client_pay = escrow_amount
+        .checked_sub(freelancer_share)
+        .ok_or(error!(CustomError::MathOverflow))?;
+
+    let withheld_fee = withheld_fee(
+        ctx.accounts
+            .mint
+            .as_ref()
+            .map(|mint| mint.to_account_info()),
+        &[client_pay, freelancer_pay, treasury_pay],
+    )?;
+
+    emit!(DisputeSettled {
+        dispute: ctx.accounts.dispute.key(),
+        deal: deal.key(),
+        client_share,
+        client_pay,
+        freelancer_pay,
+        treasury_pay,
+        withheld_fee,
+    });
+
+    if gig.mint.is_none() {
+        // SOL is escrowed in the deal account, its rent is left untouched.
+
+        let deal_info = deal.to_account_info();
+
+        transfer_lamports(&deal_info, &client.to_account_info(), client_pay)?;
+        transfer_lamports(
+            &deal_info,
+            &ctx.accounts.freelancer.to_account_info(),
+            freelancer_pay,
+        )?;
+        transfer_lamports(
+            &deal_info,
+            &ctx.accounts.treasury.to_account_info(),
+            treasury_pay,
+        )?;
+
+        close_program_account(deal, &client.to_account_info())?;
+
+        return close_program_account(
+            &ctx.accounts.dispute,
+            &ctx.accounts.opened_by.to_account_info(),
+        );
+    }
+
+    let escrow = escrow
+        .as_ref()
+        .ok_or(error!(CustomError::MissingTokenAccount))?;
+
+    let mint = ctx
+        .accounts
+        .mint
+        .as_ref()
+        .ok_or(error!(CustomError::MintMismatch))?;
+
+    let client_token_account = ctx
+        .accounts
+        .client_token_account
+        .as_ref()
+        .ok_or(error!(CustomError::MissingTokenAccount))?;
+
+    let freelancer_token_account = ctx
+        .accounts
+        .freelancer_token_account
+        .as_ref()
+        .ok_or(error!(CustomError::MissingTokenAccount))?;
+
+    let treasury_token_account = ctx
+        .accounts
+        .treasury_token_account
+        .as_ref()
+        .ok_or(error!(CustomError::MissingTokenAccount))?;
+
+    let gig_key = gig.key();
+    let client_key = client.key();
+    let deal_bump = deal.bump.to_le_bytes();
+
+    let inner = vec![
+        b"deal".as_ref(),
+        client_key.as_ref(),
+        gig_key.as_ref(),
+        deal.nonce.as_ref(),
+        deal_bump.as_ref(),
+    ];
+
+    let deal_sig = vec![inner.as_slice()];
+
+    // Transfer to client.
+
+    let transfer_ix = TransferChecked {
+        from: escrow.to_account_info(),
+        mint: mint.to_account_info(),
+        to: client_token_account.to_account_info(),
+        authority: deal.to_account_info(),
+    };
+
+    let cpi_ctx = CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        transfer_ix,
+        deal_sig.as_slice(),
+    );
+
+    anchor_spl::token_interface::transfer_checked(cpi_ctx, client_pay, mint.decimals)?;
+
+    // Transfer to freelancer.
+
+    let transfer_ix = TransferChecked {
+        from: escrow.to_account_info(),
+        mint: mint.to_account_info(),
+        to: freelancer_token_account.to_account_info(),
+        authority: deal.to_account_info(),
+    };
+
+    let cpi_ctx = CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        transfer_ix,
+        deal_sig.as_slice(),
+    );
+
+    anchor_spl::token_interface::transfer_checked(cpi_ctx, freelancer_pay, mint.decimals)?;
+
+    // Transfer to treasury.
+
+    let transfer_ix = TransferChecked {
+        from: escrow.to_account_info(),
+        mint: mint.to_account_info(),
+        to: treasury_token_account.to_account_info(),
+        authority: deal.to_account_info(),
+    };
+
+    let cpi_ctx = CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        transfer_ix,
+        deal_sig.as_slice(),
+    );
+
+    anchor_spl::token_interface::transfer_checked(cpi_ctx, treasury_pay, mint.decimals)?;
+
+    // Close escrow account, give back the rent to client.
+
+    close_escrow(
+        deal,
+        escrow,
+        mint,
+        &client.to_account_info(),
+        &ctx.accounts.token_program.to_account_info(),
+    )?;
+
+    close_program_account(deal, &client.to_account_info())?;
+
+    close_program_account(
+        &ctx.accounts.dispute,
+        &ctx.accounts.opened_by.to_account_info(),
+    )
+}
@@ -0,0 +1,50 @@
+use anchor_lang::{prelude::*, solana_program::clock};
+
+use crate::{
+    events::DeliverySubmitted,
+    states::{Deal, DealState},
+    CustomError,
+};
+
+#[derive(AnchorDeserialize, AnchorSerialize)]
+pub struct SubmitDeliveryParams {
+    pub delivery: [u8; 32],
+}
+
+#[derive(Accounts)]
+#[instruction(params: SubmitDeliveryParams)]
+pub struct SubmitDelivery<'info> {
+    #[account(
+        mut,
+        constraint = deal.freelancer.key() == freelancer.key() @ CustomError::Unauthorized,
+    )]
+    pub deal: Account<'info, Deal>,
+
+    pub freelancer: Signer<'info>,
+}
+
+/// Submitting again replaces the delivery and restarts the client's review window.
+pub fn submit_delivery_handler(
+    ctx: Context<SubmitDelivery>,
+    params: SubmitDeliveryParams,
+) -> Result<()> {
+    let clock = clock::Clock::get()?;
+    let deal = &mut ctx.accounts.deal;
+
+    deal.require_state(DealState::Accepted)?;
+
+    // Past this point the client can claim the escrow back.
+    if clock.unix_timestamp > deal.expires_at() {
+        return Err(error!(CustomError::DeadlinePassed));
+    }
+
+    deal.delivery = params.delivery;
+    deal.time_delivered = clock.unix_timestamp;
+
+    emit!(DeliverySubmitted {
+        deal: deal.key(),
+        delivery: deal.delivery,
+    });
+
+    Ok(())
+}
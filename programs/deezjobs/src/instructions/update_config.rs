@@ -0,0 +1,51 @@
+use crate::{states::Config, CustomError};
+use anchor_lang::prelude::*;
+
+#[derive(AnchorDeserialize, AnchorSerialize)]
+pub struct UpdateConfigParams {
+    pub client_fee_percentage: u16,
+    pub freelancer_fee_percentage: u16,
+    pub referral_fee_percentage: u16,
+    pub review_window: i64,
+    pub governance_mint: Pubkey,
+    pub dispute_vote_duration: i64,
+    pub dispute_quorum: u64,
+}
+
+#[derive(Accounts)]
+#[instruction(params: UpdateConfigParams)]
+pub struct UpdateConfig<'info> {
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, Config>,
+
+    /// CHECK: pass the current treasury to keep it
+    pub treasury: UncheckedAccount<'info>,
+
+    #[account(
+        constraint = authority.key() == config.authority.key() @ CustomError::Unauthorized,
+    )]
+    pub authority: Signer<'info>,
+}
+
+pub fn update_config_handler(ctx: Context<UpdateConfig>, params: UpdateConfigParams) -> Result<()> {
+    let config = &mut ctx.accounts.config;
+
+    config.treasury = ctx.accounts.treasury.key();
+    config.client_fee_percentage = params.client_fee_percentage;
+    config.freelancer_fee_percentage = params.freelancer_fee_percentage;
+    config.referral_fee_percentage = params.referral_fee_percentage;
+    config.review_window = params.review_window;
+
+    // Disputes already raised keep the voting settings they were raised with.
+    config.governance_mint = params.governance_mint;
+    config.dispute_vote_duration = params.dispute_vote_duration;
+    config.dispute_quorum = params.dispute_quorum;
+
+    config.validate_fees()?;
+    config.validate_disputes()?;
+    config.validate_review_window()
+}
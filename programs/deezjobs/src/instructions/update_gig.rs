@@ -0,0 +1,41 @@
+use crate::{events::GigUpdated, states::Gig, CustomError};
+use anchor_lang::prelude::*;
+
+#[derive(AnchorDeserialize, AnchorSerialize)]
+pub struct UpdateGigParams {
+    pub category: u8,
+    pub skills: u64,
+    pub asking: u64,
+    pub min_completion_time: i64,
+    pub accepts_referrals: bool,
+}
+
+#[derive(Accounts)]
+#[instruction(params: UpdateGigParams)]
+pub struct UpdateGig<'info> {
+    #[account(
+        mut,
+        constraint = gig.owner.key() == owner.key() @ CustomError::Unauthorized,
+    )]
+    pub gig: Account<'info, Gig>,
+
+    pub owner: Signer<'info>,
+}
+
+/// Existing deals keep the offer and deadline they were created with.
+pub fn update_gig_handler(ctx: Context<UpdateGig>, params: UpdateGigParams) -> Result<()> {
+    let gig = &mut ctx.accounts.gig;
+    gig.category = params.category;
+    gig.skills = params.skills;
+    gig.asking = params.asking;
+    gig.min_completion_time = params.min_completion_time;
+    gig.accepts_referrals = params.accepts_referrals;
+
+    emit!(GigUpdated {
+        gig: gig.key(),
+        asking: gig.asking,
+        min_completion_time: gig.min_completion_time,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,50 @@
+use crate::{
+    states::{Config, MintConfig},
+    CustomError,
+};
+use anchor_lang::prelude::*;
+
+#[derive(AnchorDeserialize, AnchorSerialize)]
+pub struct UpdateMintParams {
+    pub enabled: bool,
+    pub client_fee_min: u64,
+    pub client_fee_percentage: Option<u16>,
+    pub freelancer_fee_percentage: Option<u16>,
+    pub referral_fee_percentage: Option<u16>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateMint<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"mint",
+            mint_config.mint.as_ref(),
+        ],
+        bump = mint_config.bump,
+    )]
+    pub mint_config: Account<'info, MintConfig>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        constraint = authority.key() == config.authority.key() @ CustomError::Unauthorized,
+    )]
+    pub authority: Signer<'info>,
+}
+
+pub fn update_mint_handler(ctx: Context<UpdateMint>, params: UpdateMintParams) -> Result<()> {
+    let mint_config = &mut ctx.accounts.mint_config;
+
+    mint_config.enabled = params.enabled;
+    mint_config.client_fee_min = params.client_fee_min;
+    mint_config.client_fee_percentage = params.client_fee_percentage;
+    mint_config.freelancer_fee_percentage = params.freelancer_fee_percentage;
+    mint_config.referral_fee_percentage = params.referral_fee_percentage;
+
+    mint_config.validate_fees(&ctx.accounts.config)
+}
@@ -0,0 +1,110 @@
+use anchor_lang::{prelude::*, solana_program::clock};
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token::{CloseAccount, Mint, Token, TokenAccount, Transfer},
+};
+
+use crate::{states::DisputeVote, utils::close_program_account, CustomError};
+
+#[derive(Accounts)]
+pub struct WithdrawVote<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"vote",
+            dispute.key().as_ref(),
+            voter.key().as_ref(),
+        ],
+        bump = vote.bump,
+    )]
+    pub vote: Box<Account<'info, DisputeVote>>,
+
+    #[account(
+        mut,
+        associated_token::mint = governance_mint,
+        associated_token::authority = vote,
+    )]
+    pub vote_vault: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        associated_token::mint = governance_mint,
+        associated_token::authority = voter,
+    )]
+    pub voter_wallet: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        constraint = dispute.key() == vote.dispute.key() @ CustomError::VoteMismatch,
+    )]
+    /// CHECK: may already be closed by the settlement, only its address is used
+    pub dispute: UncheckedAccount<'info>,
+
+    pub governance_mint: Box<Account<'info, Mint>>,
+
+    #[account(mut)]
+    pub voter: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
+
+pub fn withdraw_vote_handler(ctx: Context<WithdrawVote>) -> Result<()> {
+    let clock = clock::Clock::get()?;
+    let vote = &mut ctx.accounts.vote;
+    let vote_vault = &mut ctx.accounts.vote_vault;
+    let voter = &mut ctx.accounts.voter;
+    let dispute = &ctx.accounts.dispute;
+
+    if clock.unix_timestamp < vote.voting_ends_at {
+        return Err(error!(CustomError::VotingInProgress));
+    }
+
+    let dispute_key = dispute.key();
+    let voter_key = voter.key();
+    let vote_bump = vote.bump.to_le_bytes();
+
+    let inner = vec![
+        b"vote".as_ref(),
+        dispute_key.as_ref(),
+        voter_key.as_ref(),
+        vote_bump.as_ref(),
+    ];
+
+    let vote_sig = vec![inner.as_slice()];
+
+    // Unlock the voting power.
+
+    let transfer_ix = Transfer {
+        from: vote_vault.to_account_info(),
+        to: ctx.accounts.voter_wallet.to_account_info(),
+        authority: vote.to_account_info(),
+    };
+
+    let cpi_ctx = CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        transfer_ix,
+        vote_sig.as_slice(),
+    );
+
+    anchor_spl::token::transfer(cpi_ctx, vote_vault.amount)?;
+
+    // Close vote vault
+
+    let cpi_close = CloseAccount {
+        account: vote_vault.to_account_info(),
+        destination: voter.to_account_info(),
+        authority: vote.to_account_info(),
+    };
+
+    let cpi_ctx = CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        cpi_close,
+        vote_sig.as_slice(),
+    );
+
+    anchor_spl::token::close_account(cpi_ctx)?;
+
+    // Close vote account
+
+    close_program_account(vote, &voter.to_account_info())
+}
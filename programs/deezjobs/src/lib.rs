@@ -1,7 +1,10 @@
 use anchor_lang::prelude::*;
 
+pub mod credential;
+pub mod events;
 pub mod instructions;
 pub mod states;
+pub mod utils;
 
 pub use instructions::*;
 
@@ -15,14 +18,58 @@ pub mod deezjobs {
         initialize_handler(ctx, params)
     }
 
+    pub fn update_config(ctx: Context<UpdateConfig>, params: UpdateConfigParams) -> Result<()> {
+        update_config_handler(ctx, params)
+    }
+
+    pub fn propose_authority(ctx: Context<ProposeAuthority>) -> Result<()> {
+        propose_authority_handler(ctx)
+    }
+
+    pub fn accept_authority(ctx: Context<AcceptAuthority>) -> Result<()> {
+        accept_authority_handler(ctx)
+    }
+
+    pub fn set_pause(ctx: Context<SetPause>, params: SetPauseParams) -> Result<()> {
+        set_pause_handler(ctx, params)
+    }
+
+    pub fn add_mint(ctx: Context<AddMint>, params: AddMintParams) -> Result<()> {
+        add_mint_handler(ctx, params)
+    }
+
+    pub fn update_mint(ctx: Context<UpdateMint>, params: UpdateMintParams) -> Result<()> {
+        update_mint_handler(ctx, params)
+    }
+
+    pub fn disable_mint(ctx: Context<UpdateMint>) -> Result<()> {
+        disable_mint_handler(ctx)
+    }
+
+    pub fn create_collection(ctx: Context<CreateCollection>) -> Result<()> {
+        create_collection_handler(ctx)
+    }
+
     pub fn create_gig(ctx: Context<CreateGig>, params: CreateGigParams) -> Result<()> {
         create_gig_handler(ctx, params)
     }
 
+    pub fn update_gig(ctx: Context<UpdateGig>, params: UpdateGigParams) -> Result<()> {
+        update_gig_handler(ctx, params)
+    }
+
+    pub fn set_gig_state(ctx: Context<SetGigState>, params: SetGigStateParams) -> Result<()> {
+        set_gig_state_handler(ctx, params)
+    }
+
     pub fn close_gig(ctx: Context<CloseGig>) -> Result<()> {
         close_gig_handler(ctx)
     }
 
+    pub fn register_referrer(ctx: Context<RegisterReferrer>) -> Result<()> {
+        register_referrer_handler(ctx)
+    }
+
     pub fn create_deal(ctx: Context<CreateDeal>, params: CreateDealParams) -> Result<()> {
         create_deal_handler(ctx, params)
     }
@@ -31,13 +78,75 @@ pub mod deezjobs {
         close_deal_handler(ctx)
     }
 
-    pub fn accept_deal(ctx: Context<AcceptDeal>) -> Result<()> {
-        accept_deal_handler(ctx)
+    pub fn accept_deal(ctx: Context<AcceptDeal>, params: AcceptDealParams) -> Result<()> {
+        accept_deal_handler(ctx, params)
+    }
+
+    pub fn submit_delivery(
+        ctx: Context<SubmitDelivery>,
+        params: SubmitDeliveryParams,
+    ) -> Result<()> {
+        submit_delivery_handler(ctx, params)
+    }
+
+    pub fn reject_deal(ctx: Context<RejectDeal>, params: RejectDealParams) -> Result<()> {
+        reject_deal_handler(ctx, params)
+    }
+
+    pub fn claim_expired(ctx: Context<ClaimExpired>) -> Result<()> {
+        claim_expired_handler(ctx)
+    }
+
+    pub fn counter_offer(ctx: Context<CounterOffer>, params: CounterOfferParams) -> Result<()> {
+        counter_offer_handler(ctx, params)
+    }
+
+    pub fn accept_counter(ctx: Context<UpdateOffer>, params: AcceptCounterParams) -> Result<()> {
+        accept_counter_handler(ctx, params)
+    }
+
+    pub fn revise_offer(ctx: Context<UpdateOffer>, params: ReviseOfferParams) -> Result<()> {
+        revise_offer_handler(ctx, params)
     }
 
     pub fn complete_deal(ctx: Context<CompleteDeal>) -> Result<()> {
         complete_deal_handler(ctx)
     }
+
+    pub fn release_milestone(
+        ctx: Context<CompleteDeal>,
+        params: ReleaseMilestoneParams,
+    ) -> Result<()> {
+        release_milestone_handler(ctx, params)
+    }
+
+    pub fn auto_complete(ctx: Context<CompleteDeal>) -> Result<()> {
+        auto_complete_handler(ctx)
+    }
+
+    pub fn leave_review(ctx: Context<LeaveReview>, params: LeaveReviewParams) -> Result<()> {
+        leave_review_handler(ctx, params)
+    }
+
+    pub fn raise_dispute(ctx: Context<RaiseDispute>, params: RaiseDisputeParams) -> Result<()> {
+        raise_dispute_handler(ctx, params)
+    }
+
+    pub fn settle_dispute(ctx: Context<SettleDispute>, params: SettleDisputeParams) -> Result<()> {
+        settle_dispute_handler(ctx, params)
+    }
+
+    pub fn cast_vote(ctx: Context<CastVote>, params: CastVoteParams) -> Result<()> {
+        cast_vote_handler(ctx, params)
+    }
+
+    pub fn withdraw_vote(ctx: Context<WithdrawVote>) -> Result<()> {
+        withdraw_vote_handler(ctx)
+    }
+
+    pub fn finalize_dispute(ctx: Context<SettleDispute>) -> Result<()> {
+        finalize_dispute_handler(ctx)
+    }
 }
 
 #[error_code]
@@ -47,4 +156,157 @@ pub enum CustomError {
 
     #[msg("Deadline is not long enough")]
     DeadlineTooShort,
+
+    #[msg("Deal has an open dispute")]
+    DealHasDispute,
+
+    #[msg("Share must be between 0 and 10000")]
+    InvalidShare,
+
+    #[msg("Signer is not allowed to perform this action")]
+    Unauthorized,
+
+    #[msg("Arithmetic overflow")]
+    MathOverflow,
+
+    #[msg("Deal does not belong to this gig")]
+    GigMismatch,
+
+    #[msg("Mint does not match the payment token of the gig")]
+    MintMismatch,
+
+    #[msg("Client account does not match the deal")]
+    ClientMismatch,
+
+    #[msg("Freelancer account does not match the deal")]
+    FreelancerMismatch,
+
+    #[msg("Referrer account does not match the deal")]
+    ReferrerMismatch,
+
+    #[msg("Treasury account does not match the config")]
+    TreasuryMismatch,
+
+    #[msg("Payer account does not match the gig")]
+    PayerMismatch,
+
+    #[msg("Gig still has pending deals")]
+    GigHasPendingDeals,
+
+    #[msg("Program data account does not match the program")]
+    InvalidProgramData,
+
+    #[msg("Mint is not the governance token")]
+    InvalidGovernanceMint,
+
+    #[msg("Parties of the deal cannot vote on its dispute")]
+    PartyCannotVote,
+
+    #[msg("Account does not match the party who raised the dispute")]
+    OpenerMismatch,
+
+    #[msg("Vote does not belong to this dispute")]
+    VoteMismatch,
+
+    #[msg("Voting on the dispute is still in progress")]
+    VotingInProgress,
+
+    #[msg("Voting on the dispute has already closed")]
+    VotingClosed,
+
+    #[msg("Dispute vote reached the quorum, only the community can settle it")]
+    DisputeHasVotes,
+
+    #[msg("Dispute has no votes")]
+    NoVotes,
+
+    #[msg("Dispute vote did not reach the quorum")]
+    QuorumNotReached,
+
+    #[msg("Dispute quorum and vote duration must be greater than zero")]
+    InvalidDisputeSettings,
+
+    #[msg("Vote weight must be greater than zero")]
+    InvalidVoteWeight,
+
+    #[msg("Token accounts are required for gigs paid in SPL tokens")]
+    MissingTokenAccount,
+
+    #[msg("Fee percentage is out of range")]
+    InvalidFee,
+
+    #[msg("This instruction is paused")]
+    Paused,
+
+    #[msg("Gig is not published")]
+    InvalidGigState,
+
+    #[msg("Gig cannot move to this state")]
+    InvalidGigTransition,
+
+    #[msg("Deal cannot move to this state")]
+    InvalidDealTransition,
+
+    #[msg("Deal is not in the right state for this instruction")]
+    InvalidDealState,
+
+    #[msg("Terms of the deal changed before they were accepted")]
+    TermsMismatch,
+
+    #[msg("Deal has no counter offer")]
+    NoCounterOffer,
+
+    #[msg("Milestones must add up to the offer and be due in order between now and the deadline")]
+    InvalidMilestones,
+
+    #[msg("Milestone does not exist or was already released")]
+    InvalidMilestone,
+
+    #[msg("Deal deadline or the due date of a milestone has not passed yet")]
+    DeadlineNotReached,
+
+    #[msg("Freelancer already submitted a delivery")]
+    DealDelivered,
+
+    #[msg("Deal has no delivery to review")]
+    NoDelivery,
+
+    #[msg("Client can still review the delivery")]
+    ReviewWindowOpen,
+
+    #[msg("Review window must be greater than zero")]
+    InvalidReviewWindow,
+
+    #[msg("A completed deal already used this address, pick another id")]
+    DealAddressUsed,
+
+    #[msg("Deal deadline or the due date of the next milestone has passed")]
+    DeadlinePassed,
+
+    #[msg("Mint is not accepted for payments")]
+    MintNotListed,
+
+    #[msg("Mint is disabled for new deals")]
+    MintDisabled,
+
+    #[msg("Credential token account does not match the holder")]
+    CredentialMismatch,
+
+    #[msg("Deal is not completed yet")]
+    DealNotCompleted,
+
+    #[msg("Score must be between 1 and 5")]
+    InvalidScore,
+
+    #[msg("Gig does not accept referrals")]
+    ReferralsDisabled,
+
+    #[msg("Referrer cannot be the client or the owner of the gig")]
+    InvalidReferrer,
+
+    #[msg("Owner of the gig cannot be the client of its deals")]
+    SelfDeal,
+
+    #[msg("Parties cannot review themselves")]
+    SelfReview,
 }
@@ -1,5 +1,7 @@
 use anchor_lang::prelude::*;
 
+use crate::CustomError;
+
 /// Config account serves as the global settings of the protocol.
 #[account]
 pub struct Config {
@@ -9,24 +11,89 @@ pub struct Config {
     /// Super authority. (32)
     pub authority: Pubkey,
 
+    /// Proposed new authority, it has to accept before the transfer takes effect. (33)
+    pub pending_authority: Option<Pubkey>,
+
+    /// Designated arbiter who can settle disputes alongside the authority. (32)
+    pub arbiter: Pubkey,
+
     /// Account which will hold all collected fee. (32)
     pub treasury: Pubkey,
 
     /// Fee to collect from the client for every accepted deal. Percentage with 2 decimal places (0 to 10000). (2)
     pub client_fee_percentage: u16,
 
-    /// If client fee is less than this value, this value will serve as the fee (in USDC). 6 decimal places. (8)
-    pub client_fee_min: u64,
-
     /// Fee to collect from the freelancer for each completed deal. Percentage with 2 decimal places (0 to 10000). (2)
     pub freelancer_fee_percentage: u16,
 
     /// Bounty of the referral when the deal is completed. Percentage with 2 decimal places (0 to 10000). (2)
     pub referral_fee_percentage: u16,
+
+    /// Token used as voting power on disputes. (32)
+    pub governance_mint: Pubkey,
+
+    /// How long the community can vote on a dispute once it's raised. In seconds. (8)
+    pub dispute_vote_duration: i64,
+
+    /// Minimum voting power cast on a dispute for the community's decision to apply. (8)
+    /// Below it, the authority or the arbiter settles the dispute instead.
+    pub dispute_quorum: u64,
+
+    /// How long the client has to review a delivery before anyone can complete the deal. In seconds. (8)
+    pub review_window: i64,
+
+    /// Instructions that are currently paused. Exiting a deal is never paused. (1)
+    /// * 1 - create_gig
+    /// * 2 - create_deal
+    /// * 4 - accept_deal
+    pub paused: u8,
 }
 
 impl Config {
     pub fn len() -> usize {
-        8 + 1 + 32 + 32 + 2 + 8 + 2 + 2
+        8 + 1 + 32 + 33 + 32 + 32 + 2 + 2 + 2 + 32 + 8 + 8 + 8 + 1
+    }
+
+    pub fn validate_fees(&self) -> Result<()> {
+        Config::check_fees(
+            self.client_fee_percentage,
+            self.freelancer_fee_percentage,
+            self.referral_fee_percentage,
+        )
+    }
+
+    /// Without a quorum, a single vote would take the dispute out of the arbiter's hands.
+    pub fn validate_disputes(&self) -> Result<()> {
+        if self.dispute_quorum == 0 || self.dispute_vote_duration <= 0 {
+            return Err(error!(CustomError::InvalidDisputeSettings));
+        }
+
+        Ok(())
+    }
+
+    /// The client must get some time to review a delivery before anyone can complete the deal.
+    pub fn validate_review_window(&self) -> Result<()> {
+        if self.review_window <= 0 {
+            return Err(error!(CustomError::InvalidReviewWindow));
+        }
+
+        Ok(())
+    }
+
+    /// Fees are percentages, and the freelancer's fee and the referral bounty are both taken from the offer.
+    pub fn check_fees(
+        client_fee_percentage: u16,
+        freelancer_fee_percentage: u16,
+        referral_fee_percentage: u16,
+    ) -> Result<()> {
+        if client_fee_percentage > 100_00
+            || freelancer_fee_percentage > 100_00
+            || referral_fee_percentage > 100_00
+            || freelancer_fee_percentage + referral_fee_percentage > 100_00
+        {
+            return Err(error!(CustomError::InvalidFee));
+        }
+
+        Ok(())
     }
 }
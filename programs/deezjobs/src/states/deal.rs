@@ -1,20 +1,79 @@
 use anchor_lang::prelude::*;
 
+use crate::CustomError;
+
+#[derive(AnchorDeserialize, AnchorSerialize, Clone, Copy, PartialEq, Eq)]
+pub enum DealState {
+    /// Offered by the client, waiting for the freelancer.
+    Open,
+    Accepted,
+    Disputed,
+    /// The deal account is closed right after reaching one of these states.
+    Cancelled,
+    Expired,
+    Completed,
+    /// Escrow was split by the arbiter or the community.
+    Settled,
+}
+
+impl DealState {
+    /// | From     | To        | Instruction                                        |
+    /// |----------|-----------|----------------------------------------------------|
+    /// | Open     | Accepted  | accept_deal                                        |
+    /// | Open     | Cancelled | close_deal, reject_deal                            |
+    /// | Accepted | Disputed  | raise_dispute                                      |
+    /// | Accepted | Expired   | claim_expired                                      |
+    /// | Accepted | Completed | complete_deal, auto_complete, last release_milestone |
+    /// | Disputed | Settled   | settle_dispute, finalize_dispute                   |
+    pub fn can_transition_to(&self, to: DealState) -> bool {
+        matches!(
+            (self, to),
+            (DealState::Open, DealState::Accepted)
+                | (DealState::Open, DealState::Cancelled)
+                | (DealState::Accepted, DealState::Disputed)
+                | (DealState::Accepted, DealState::Expired)
+                | (DealState::Accepted, DealState::Completed)
+                | (DealState::Disputed, DealState::Settled)
+        )
+    }
+}
+
+/// Maximum number of milestones a deal can be split into.
+pub const MAX_MILESTONES: usize = 8;
+
+#[derive(AnchorDeserialize, AnchorSerialize, Clone)]
+pub struct Milestone {
+    /// Portion of the offer paid when this milestone is released. (8)
+    pub amount: u64,
+
+    /// Milestone should be delivered before this date, the client can claim the escrow back otherwise. Unix timestamp. (8)
+    pub due_date: i64,
+
+    /// Whether the client already released this milestone. (1)
+    pub released: bool,
+}
+
+impl Milestone {
+    pub fn len() -> usize {
+        8 + 8 + 1
+    }
+}
+
 /// A Deal is an account created by the client, related to the Gig created by the freelancer.
+/// If the Gig is paid in SOL, the escrow is held by this account on top of its rent.
 #[account]
 pub struct Deal {
     /// Bump nonce of the PDA. (1)
     pub bump: u8,
 
+    /// Random seed sliced from a pubkey, lets the same client open several deals on a gig. (8)
+    pub nonce: [u8; 8],
+
     /// Owner of this deal. (32)
     pub client: Pubkey,
 
     /// State of this Deal. (1)
-    /// * 1  - Published
-    /// * 2  - Accepted by the freelancer
-    /// * 4  - Has dispute
-    /// * 8  - Completed / settled
-    pub state: u8,
+    pub state: DealState,
 
     /// The owner of the Gig. (32)
     pub freelancer: Pubkey,
@@ -36,10 +95,87 @@ pub struct Deal {
 
     /// Address who referred the gig to the client. (33)
     pub referrer: Option<Pubkey>,
+
+    /// Offer proposed by the freelancer, waiting for the client to accept. (9)
+    pub counter_offer: Option<u64>,
+
+    /// Deadline proposed along with the counter offer. Unix timestamp. (8)
+    pub counter_deadline: i64,
+
+    /// Optional split of the offer, empty if the offer is paid all at once. (4 + 8 * 17)
+    pub milestones: Vec<Milestone>,
+
+    /// Hash of the deliverable submitted by the freelancer, the deliverable itself is kept off-chain. (32)
+    pub delivery: [u8; 32],
+
+    /// Time the freelancer submitted the delivery, 0 if nothing was delivered. Unix timestamp. (8)
+    pub time_delivered: i64,
+
+    /// Fee percentages in effect when the deal was created, later changes to the configs do not apply. (2 * 3)
+    pub client_fee_percentage: u16,
+    pub freelancer_fee_percentage: u16,
+    pub referral_fee_percentage: u16,
+
+    /// Review window of the config when the deal was created, in seconds. (8)
+    pub review_window: i64,
 }
 
 impl Deal {
     pub fn len() -> usize {
-        8 + 1 + 32 + 1 + 32 + 32 + 8 + 8 + 8 + 8 + 33
+        8 + 1 + 8 + 32 + 1 + 32 + 32 + 8 + 8 + 8 + 8 + 33 + 9 + 8 + 4 + MAX_MILESTONES * Milestone::len() + 32 + 8 + 2 * 3 + 8
+    }
+
+    pub fn transition(&mut self, to: DealState) -> Result<()> {
+        if self.state == DealState::Disputed && to != DealState::Settled {
+            return Err(error!(CustomError::DealHasDispute));
+        }
+
+        if !self.state.can_transition_to(to) {
+            return Err(error!(CustomError::InvalidDealTransition));
+        }
+
+        self.state = to;
+
+        Ok(())
+    }
+
+    /// For instructions which act on the deal without moving it to another state.
+    pub fn require_state(&self, state: DealState) -> Result<()> {
+        if self.state == state {
+            return Ok(());
+        }
+
+        match self.state {
+            DealState::Disputed => Err(error!(CustomError::DealHasDispute)),
+            _ => Err(error!(CustomError::InvalidDealState)),
+        }
+    }
+
+    /// Part of the offer that is still held in escrow.
+    pub fn remaining_offer(&self) -> u64 {
+        if self.milestones.is_empty() {
+            return self.offer;
+        }
+
+        self.milestones
+            .iter()
+            .filter(|milestone| !milestone.released)
+            .map(|milestone| milestone.amount)
+            .sum()
+    }
+
+    /// Whether the whole offer was paid out, a deal without milestones is paid all at once.
+    pub fn is_fully_released(&self) -> bool {
+        self.milestones.iter().all(|milestone| milestone.released)
+    }
+
+    /// Delivery is overdue past this time, the earliest due date of the milestones left or the deadline.
+    pub fn expires_at(&self) -> i64 {
+        self.milestones
+            .iter()
+            .filter(|milestone| !milestone.released)
+            .map(|milestone| milestone.due_date)
+            .min()
+            .unwrap_or(self.deadline)
     }
 }
@@ -0,0 +1,50 @@
+use anchor_lang::prelude::*;
+
+/// A Dispute is raised by either party of an accepted Deal when they cannot agree on its outcome.
+#[account]
+pub struct Dispute {
+    /// Bump nonce of the PDA. (1)
+    pub bump: u8,
+
+    /// The disputed Deal. (32)
+    pub deal: Pubkey,
+
+    /// Party who raised the dispute, either the client or the freelancer. (32)
+    pub opened_by: Pubkey,
+
+    /// Reason of the dispute. (1)
+    /// * 0 - Other
+    /// * 1 - Work was not delivered
+    /// * 2 - Work does not match the agreed scope
+    /// * 3 - Client is unresponsive
+    /// * 4 - Payment is withheld
+    pub reason: u8,
+
+    /// Hash of the off-chain evidence, eg. SHA-256 of the document or an IPFS CID digest. (32)
+    pub evidence: [u8; 32],
+
+    /// Time this dispute was raised. Unix timestamp. (8)
+    pub time_opened: i64,
+
+    /// Voting on the outcome is open until this time. Unix timestamp. (8)
+    pub voting_ends_at: i64,
+
+    /// Token used as voting power, copied from the Config when the dispute is raised. (32)
+    pub governance_mint: Pubkey,
+
+    /// Minimum voting power for the community's decision to apply, copied from the Config. (8)
+    pub quorum: u64,
+
+    /// Total voting power cast on this dispute. (8)
+    pub total_weight: u64,
+
+    /// Sum of every vote's client share multiplied by its weight. (16)
+    /// The resulting client share is this value divided by `total_weight`.
+    pub weighted_client_share: u128,
+}
+
+impl Dispute {
+    pub fn len() -> usize {
+        8 + 1 + 32 + 32 + 1 + 32 + 8 + 8 + 32 + 8 + 8 + 16
+    }
+}
@@ -0,0 +1,32 @@
+use anchor_lang::prelude::*;
+
+/// A DisputeVote is cast by a governance token holder on the outcome of a Dispute.
+/// The voting power is locked in the vote's vault until the voting window closes.
+#[account]
+pub struct DisputeVote {
+    /// Bump nonce of the PDA. (1)
+    pub bump: u8,
+
+    /// The Dispute being voted on. (32)
+    pub dispute: Pubkey,
+
+    /// Holder who cast this vote. (32)
+    pub voter: Pubkey,
+
+    /// Amount of governance tokens locked for this vote. (8)
+    pub weight: u64,
+
+    /// Portion of the offer the voter wants refunded to the client. Percentage with 2 decimal places (0 to 10000). (2)
+    /// * 0     - Everything goes to the freelancer
+    /// * 10000 - Everything goes to the client
+    pub client_share: u16,
+
+    /// Copied from the Dispute, which is closed once settled. Unix timestamp. (8)
+    pub voting_ends_at: i64,
+}
+
+impl DisputeVote {
+    pub fn len() -> usize {
+        8 + 1 + 32 + 32 + 8 + 2 + 8
+    }
+}
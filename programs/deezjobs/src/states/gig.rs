@@ -1,4 +1,38 @@
 use anchor_lang::prelude::*;
+use anchor_spl::token::spl_token::native_mint;
+
+use crate::{utils::add_rating, CustomError};
+
+#[derive(AnchorDeserialize, AnchorSerialize, Clone, Copy, PartialEq, Eq)]
+pub enum GigState {
+    Draft,
+    /// The only state accepting new deals.
+    Published,
+    /// Pending deals keep running.
+    Paused,
+    Archived,
+}
+
+impl GigState {
+    /// | From               | To        |
+    /// |--------------------|-----------|
+    /// | Draft, Paused      | Published |
+    /// | Published          | Paused    |
+    /// | Draft, Published, Paused | Archived |
+    /// | Archived           | Draft     |
+    pub fn can_transition_to(&self, to: GigState) -> bool {
+        matches!(
+            (self, to),
+            (GigState::Draft | GigState::Paused, GigState::Published)
+                | (GigState::Published, GigState::Paused)
+                | (
+                    GigState::Draft | GigState::Published | GigState::Paused,
+                    GigState::Archived
+                )
+                | (GigState::Archived, GigState::Draft)
+        )
+    }
+}
 
 /// Gig is an account created by the freelancer which tells the details of the offered service.
 #[account]
@@ -10,8 +44,7 @@ pub struct Gig {
     pub owner: Pubkey,
 
     /// State of this Gig. (1)
-    /// * 1 - Published
-    pub state: u8,
+    pub state: GigState,
 
     /// How many deals are currently accepted and ongoing. (1)
     /// Increments when user accepts a Deal.
@@ -41,10 +74,38 @@ pub struct Gig {
     /// SPL token for the gig payment. (33)
     /// If set to None, the freelancer is expecting SOL as payment.
     pub mint: Option<Pubkey>,
+
+    /// Sum of the scores left by clients, the average is `rating_total / rating_count`. (8)
+    pub rating_total: u64,
+
+    /// Number of reviews left by clients. (4)
+    pub rating_count: u32,
+
+    /// Whether deals can be referred, the referral bounty is taken from the freelancer's pay. (1)
+    pub accepts_referrals: bool,
 }
 
 impl Gig {
     pub fn len() -> usize {
-        8 + 1 + 32 + 1 + 1 + 1 + 8 + 8 + 8 + 32 + 8 + 33
+        8 + 1 + 32 + 1 + 1 + 1 + 8 + 8 + 8 + 32 + 8 + 33 + 8 + 4 + 1
+    }
+
+    /// Mint listed in the MintConfig of this gig, the native mint stands for SOL.
+    pub fn payment_mint(&self) -> Pubkey {
+        self.mint.unwrap_or(native_mint::ID)
+    }
+
+    pub fn record_rating(&mut self, score: u8) -> Result<()> {
+        add_rating(&mut self.rating_total, &mut self.rating_count, score)
+    }
+
+    pub fn transition(&mut self, to: GigState) -> Result<()> {
+        if !self.state.can_transition_to(to) {
+            return Err(error!(CustomError::InvalidGigTransition));
+        }
+
+        self.state = to;
+
+        Ok(())
     }
 }
@@ -0,0 +1,77 @@
+use anchor_lang::prelude::*;
+
+use crate::{states::Config, CustomError};
+
+/// MintConfig lists a token accepted as payment for deals, along with its own fee settings.
+/// Gigs paid in SOL are listed under the native mint.
+#[account]
+pub struct MintConfig {
+    /// Bump nonce of the PDA. (1)
+    pub bump: u8,
+
+    /// The listed mint. (32)
+    pub mint: Pubkey,
+
+    /// New deals can only be created with enabled mints, ongoing deals are not affected. (1)
+    pub enabled: bool,
+
+    /// If client fee is less than this value, this value will serve as the fee. (8)
+    /// Note: decimal places depends on the mint.
+    pub client_fee_min: u64,
+
+    /// Overrides the client fee percentage of the Config. (3)
+    pub client_fee_percentage: Option<u16>,
+
+    /// Overrides the freelancer fee percentage of the Config. (3)
+    pub freelancer_fee_percentage: Option<u16>,
+
+    /// Overrides the referral fee percentage of the Config. (3)
+    pub referral_fee_percentage: Option<u16>,
+}
+
+impl MintConfig {
+    pub fn len() -> usize {
+        8 + 1 + 32 + 1 + 8 + 3 + 3 + 3
+    }
+
+    pub fn client_fee_percentage(&self, config: &Config) -> u16 {
+        self.client_fee_percentage
+            .unwrap_or(config.client_fee_percentage)
+    }
+
+    pub fn freelancer_fee_percentage(&self, config: &Config) -> u16 {
+        self.freelancer_fee_percentage
+            .unwrap_or(config.freelancer_fee_percentage)
+    }
+
+    pub fn referral_fee_percentage(&self, config: &Config) -> u16 {
+        self.referral_fee_percentage
+            .unwrap_or(config.referral_fee_percentage)
+    }
+
+    /// Fee collected from the client on top of the offer, at the percentage recorded on the deal.
+    pub fn client_fee(&self, client_fee_percentage: u16, offer: u64) -> Result<u64> {
+        let client_fee_percentage: u64 = client_fee_percentage.into();
+
+        let client_fee = client_fee_percentage
+            .checked_mul(offer)
+            .ok_or(error!(CustomError::MathOverflow))?
+            .checked_div(100_00)
+            .ok_or(error!(CustomError::MathOverflow))?;
+
+        if client_fee < self.client_fee_min {
+            Ok(self.client_fee_min)
+        } else {
+            Ok(client_fee)
+        }
+    }
+
+    /// Same rules as the Config, applied to the fees in effect for this mint.
+    pub fn validate_fees(&self, config: &Config) -> Result<()> {
+        Config::check_fees(
+            self.client_fee_percentage(config),
+            self.freelancer_fee_percentage(config),
+            self.referral_fee_percentage(config),
+        )
+    }
+}
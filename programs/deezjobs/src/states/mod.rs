@@ -1,7 +1,21 @@
 mod config;
 mod deal;
+mod dispute;
+mod dispute_vote;
 mod gig;
+mod mint_config;
+mod profile;
+mod receipt;
+mod referrer;
+mod review;
 
 pub use config::*;
 pub use deal::*;
+pub use dispute::*;
+pub use dispute_vote::*;
 pub use gig::*;
+pub use mint_config::*;
+pub use profile::*;
+pub use receipt::*;
+pub use referrer::*;
+pub use review::*;
@@ -0,0 +1,130 @@
+use anchor_lang::prelude::*;
+
+use crate::{utils::add_rating, CustomError};
+
+/// Maximum number of mints a profile keeps the volume of.
+pub const MAX_PROFILE_MINTS: usize = 8;
+
+#[derive(AnchorDeserialize, AnchorSerialize, Clone)]
+pub struct Volume {
+    /// Mint of the payments, the native mint for SOL. (32)
+    pub mint: Pubkey,
+
+    /// Sum of the offers paid or received in this mint. (8)
+    pub amount: u64,
+}
+
+impl Volume {
+    pub fn len() -> usize {
+        32 + 8
+    }
+}
+
+/// Profile is the reputation record of a wallet, shared by its deals as a client and as a freelancer.
+/// It can't be transferred, other dApps can read it from the wallet address alone.
+#[account]
+pub struct Profile {
+    /// Bump nonce of the PDA. (1)
+    pub bump: u8,
+
+    /// Wallet this record belongs to. (32)
+    pub owner: Pubkey,
+
+    /// Deals completed, as the client or as the freelancer. (4)
+    pub completed_deals: u32,
+
+    /// Volume of the completed and settled deals, up to MAX_PROFILE_MINTS mints. (4 + 40 * 8)
+    pub volumes: Vec<Volume>,
+
+    /// Disputes settled with more than half of the offer going to this wallet. (4)
+    pub disputes_won: u32,
+
+    /// Disputes settled with more than half of the offer going to the other party. (4)
+    pub disputes_lost: u32,
+
+    /// Deals due a delivery as the freelancer, completed or expired without one. (4)
+    pub deliveries: u32,
+
+    /// Deliveries made before the deadline, the rate is `on_time_deliveries / deliveries`. (4)
+    pub on_time_deliveries: u32,
+
+    /// Sum of the ratings received, the average is `rating_total / rating_count`. (8)
+    pub rating_total: u64,
+
+    /// Number of ratings received. (4)
+    pub rating_count: u32,
+}
+
+impl Profile {
+    pub fn len() -> usize {
+        8 + 1 + 32 + 4 + (4 + Volume::len() * MAX_PROFILE_MINTS) + 4 + 4 + 4 + 4 + 8 + 4
+    }
+
+    /// Profiles are created on the first deal that reaches them.
+    pub fn register(&mut self, bump: u8, owner: Pubkey) {
+        if self.owner == Pubkey::default() {
+            self.bump = bump;
+            self.owner = owner;
+        }
+    }
+
+    /// Volume of mints past MAX_PROFILE_MINTS is not tracked, a full record must not block the payout.
+    pub fn add_volume(&mut self, mint: Pubkey, amount: u64) -> Result<()> {
+        match self.volumes.iter_mut().find(|volume| volume.mint == mint) {
+            Some(volume) => {
+                volume.amount = volume
+                    .amount
+                    .checked_add(amount)
+                    .ok_or(error!(CustomError::MathOverflow))?;
+            }
+            None => {
+                if self.volumes.len() < MAX_PROFILE_MINTS {
+                    self.volumes.push(Volume { mint, amount });
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn record_completed(&mut self, mint: Pubkey, amount: u64) -> Result<()> {
+        self.completed_deals = self
+            .completed_deals
+            .checked_add(1)
+            .ok_or(error!(CustomError::MathOverflow))?;
+        self.add_volume(mint, amount)
+    }
+
+    pub fn record_delivery(&mut self, on_time: bool) -> Result<()> {
+        self.deliveries = self
+            .deliveries
+            .checked_add(1)
+            .ok_or(error!(CustomError::MathOverflow))?;
+
+        if on_time {
+            self.on_time_deliveries = self
+                .on_time_deliveries
+                .checked_add(1)
+                .ok_or(error!(CustomError::MathOverflow))?;
+        }
+
+        Ok(())
+    }
+
+    pub fn record_dispute(&mut self, won: bool) -> Result<()> {
+        let disputes = match won {
+            true => &mut self.disputes_won,
+            false => &mut self.disputes_lost,
+        };
+
+        *disputes = disputes
+            .checked_add(1)
+            .ok_or(error!(CustomError::MathOverflow))?;
+
+        Ok(())
+    }
+
+    pub fn record_rating(&mut self, score: u8) -> Result<()> {
+        add_rating(&mut self.rating_total, &mut self.rating_count, score)
+    }
+}
@@ -0,0 +1,39 @@
+use anchor_lang::prelude::*;
+
+/// A Receipt outlives its Deal, which is closed once the offer is fully paid out.
+/// It serves as the proof that the deal went through.
+#[account]
+pub struct Receipt {
+    /// Bump nonce of the PDA. (1)
+    pub bump: u8,
+
+    /// The closed Deal. (32)
+    pub deal: Pubkey,
+
+    /// The Gig of the deal. (32)
+    pub gig: Pubkey,
+
+    /// Owner of the deal. (32)
+    pub client: Pubkey,
+
+    /// The owner of the Gig. (32)
+    pub freelancer: Pubkey,
+
+    /// SPL token the deal was paid with, none if paid in SOL. (33)
+    pub mint: Option<Pubkey>,
+
+    /// Agreed offer amount. (8)
+    pub offer: u64,
+
+    /// Portion of the offer released so far, milestones are released one at a time. (8)
+    pub released: u64,
+
+    /// Time the last of the offer was released, 0 until then. Unix timestamp. (8)
+    pub time_completed: i64,
+}
+
+impl Receipt {
+    pub fn len() -> usize {
+        8 + 1 + 32 + 32 + 32 + 32 + 33 + 8 + 8 + 8
+    }
+}
@@ -0,0 +1,23 @@
+use anchor_lang::prelude::*;
+
+/// Referrer is the registration of a wallet allowed to earn referral bounties on deals.
+#[account]
+pub struct Referrer {
+    /// Bump nonce of the PDA. (1)
+    pub bump: u8,
+
+    /// Wallet receiving the bounties. (32)
+    pub owner: Pubkey,
+
+    /// Deals created through this referrer. (4)
+    pub referrals: u32,
+
+    /// Time the referrer registered. Unix timestamp. (8)
+    pub time_registered: i64,
+}
+
+impl Referrer {
+    pub fn len() -> usize {
+        8 + 1 + 32 + 4 + 8
+    }
+}
@@ -0,0 +1,35 @@
+use anchor_lang::prelude::*;
+
+/// Feedback left by one party of a completed deal on the other, each side reviews once.
+#[account]
+pub struct Review {
+    /// Bump nonce of the PDA. (1)
+    pub bump: u8,
+
+    /// The completed Deal. (32)
+    pub deal: Pubkey,
+
+    /// The Gig of the deal. (32)
+    pub gig: Pubkey,
+
+    /// Party who left the review. (32)
+    pub author: Pubkey,
+
+    /// Party being reviewed. (32)
+    pub subject: Pubkey,
+
+    /// Score from 1 to 5. (1)
+    pub score: u8,
+
+    /// Hash of the review content which is stored off-chain. (32)
+    pub content: [u8; 32],
+
+    /// Time the review was left. Unix timestamp. (8)
+    pub time_created: i64,
+}
+
+impl Review {
+    pub fn len() -> usize {
+        8 + 1 + 32 + 32 + 32 + 32 + 1 + 32 + 8
+    }
+}
@@ -0,0 +1,248 @@
+use anchor_lang::{prelude::*, solana_program::program::invoke, system_program};
+use anchor_spl::{
+    token_2022::spl_token_2022::{
+        self,
+        extension::{
+            transfer_fee::{instruction::harvest_withheld_tokens_to_mint, TransferFeeConfig},
+            BaseStateWithExtensions, StateWithExtensions,
+        },
+    },
+    token_interface::{CloseAccount, Mint, TokenAccount, TransferChecked},
+};
+
+use crate::{states::Deal, CustomError};
+
+/// Moves lamports out of an account owned by this program, eg. the SOL escrowed in a Deal.
+pub fn transfer_lamports(from: &AccountInfo, to: &AccountInfo, amount: u64) -> Result<()> {
+    let from_starting_lamports = from.lamports();
+    let to_starting_lamports = to.lamports();
+
+    **from.lamports.borrow_mut() = from_starting_lamports
+        .checked_sub(amount)
+        .ok_or(error!(CustomError::MathOverflow))?;
+    **to.lamports.borrow_mut() = to_starting_lamports
+        .checked_add(amount)
+        .ok_or(error!(CustomError::MathOverflow))?;
+
+    Ok(())
+}
+
+/// Adds a score to a rating kept as a sum and a count, eg. the ones of a Gig and a Profile.
+pub fn add_rating(total: &mut u64, count: &mut u32, score: u8) -> Result<()> {
+    *total = total
+        .checked_add(u64::from(score))
+        .ok_or(error!(CustomError::MathOverflow))?;
+    *count = count
+        .checked_add(1)
+        .ok_or(error!(CustomError::MathOverflow))?;
+
+    Ok(())
+}
+
+/// Closes an account owned by this program, eg. a Deal, its lamports go to `destination`.
+/// The account is handed back to the system program, Anchor would write it back on exit otherwise.
+pub fn close_program_account<'info, T>(
+    account: &Account<'info, T>,
+    destination: &AccountInfo<'info>,
+) -> Result<()>
+where
+    T: AccountSerialize + AccountDeserialize + Owner + Clone,
+{
+    account.close(destination.clone())
+}
+
+/// Creates an account at a PDA of this program. `create_account` fails once the address holds
+/// lamports, which anyone can send, so only the missing rent is transferred before allocating.
+pub fn create_pda_account<'info>(
+    payer: &AccountInfo<'info>,
+    account: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    seeds: &[&[u8]],
+    space: usize,
+    lamports: u64,
+    owner: &Pubkey,
+) -> Result<()> {
+    let missing_lamports = lamports.saturating_sub(account.lamports());
+
+    if missing_lamports > 0 {
+        system_program::transfer(
+            CpiContext::new(
+                system_program.clone(),
+                system_program::Transfer {
+                    from: payer.clone(),
+                    to: account.clone(),
+                },
+            ),
+            missing_lamports,
+        )?;
+    }
+
+    system_program::allocate(
+        CpiContext::new_with_signer(
+            system_program.clone(),
+            system_program::Allocate {
+                account_to_allocate: account.clone(),
+            },
+            &[seeds],
+        ),
+        space as u64,
+    )?;
+
+    system_program::assign(
+        CpiContext::new_with_signer(
+            system_program.clone(),
+            system_program::Assign {
+                account_to_assign: account.clone(),
+            },
+            &[seeds],
+        ),
+        owner,
+    )
+}
+
+/// Sends everything left in the escrow of a Deal back to the client, then closes the escrow.
+pub fn refund_escrow<'info>(
+    deal: &Account<'info, Deal>,
+    escrow: &InterfaceAccount<'info, TokenAccount>,
+    mint: &InterfaceAccount<'info, Mint>,
+    client_wallet: &InterfaceAccount<'info, TokenAccount>,
+    client: &AccountInfo<'info>,
+    token_program: &AccountInfo<'info>,
+) -> Result<()> {
+    let deal_bump = deal.bump.to_le_bytes();
+
+    let inner = vec![
+        b"deal".as_ref(),
+        deal.client.as_ref(),
+        deal.gig.as_ref(),
+        deal.nonce.as_ref(),
+        deal_bump.as_ref(),
+    ];
+
+    let deal_sig = vec![inner.as_slice()];
+
+    let transfer_ix = TransferChecked {
+        from: escrow.to_account_info(),
+        mint: mint.to_account_info(),
+        to: client_wallet.to_account_info(),
+        authority: deal.to_account_info(),
+    };
+
+    let cpi_ctx =
+        CpiContext::new_with_signer(token_program.clone(), transfer_ix, deal_sig.as_slice());
+
+    anchor_spl::token_interface::transfer_checked(cpi_ctx, escrow.amount, mint.decimals)?;
+
+    close_escrow(deal, escrow, mint, client, token_program)
+}
+
+/// Closes the emptied escrow of a Deal, its rent goes back to the client.
+/// Token-2022 refuses to close an account holding withheld transfer fees, they are moved to the
+/// mint first, where the withdraw authority of the mint can still collect them.
+pub fn close_escrow<'info>(
+    deal: &Account<'info, Deal>,
+    escrow: &InterfaceAccount<'info, TokenAccount>,
+    mint: &InterfaceAccount<'info, Mint>,
+    client: &AccountInfo<'info>,
+    token_program: &AccountInfo<'info>,
+) -> Result<()> {
+    if transfer_fee_config(&mint.to_account_info())?.is_some() {
+        let harvest_ix =
+            harvest_withheld_tokens_to_mint(token_program.key, &mint.key(), &[&escrow.key()])?;
+
+        invoke(
+            &harvest_ix,
+            &[
+                mint.to_account_info(),
+                escrow.to_account_info(),
+                token_program.clone(),
+            ],
+        )?;
+    }
+
+    let deal_bump = deal.bump.to_le_bytes();
+
+    let inner = vec![
+        b"deal".as_ref(),
+        deal.client.as_ref(),
+        deal.gig.as_ref(),
+        deal.nonce.as_ref(),
+        deal_bump.as_ref(),
+    ];
+
+    let deal_sig = vec![inner.as_slice()];
+
+    let cpi_close = CloseAccount {
+        account: escrow.to_account_info(),
+        destination: client.clone(),
+        authority: deal.to_account_info(),
+    };
+
+    let cpi_ctx =
+        CpiContext::new_with_signer(token_program.clone(), cpi_close, deal_sig.as_slice());
+
+    anchor_spl::token_interface::close_account(cpi_ctx)
+}
+
+/// Lamports held by an account on top of its rent exemption, ie. the SOL escrowed in a Deal.
+pub fn escrowed_lamports(account: &AccountInfo) -> Result<u64> {
+    let rent = Rent::get()?;
+    let escrowed = account
+        .lamports()
+        .checked_sub(rent.minimum_balance(account.data_len()))
+        .ok_or(error!(CustomError::MathOverflow))?;
+
+    Ok(escrowed)
+}
+
+/// Transfer fee settings of a Token-2022 mint, None for mints without the extension.
+/// Interest-bearing mints need no special care, interest only shows up in the UI amount.
+fn transfer_fee_config(mint: &AccountInfo) -> Result<Option<TransferFeeConfig>> {
+    if *mint.owner != spl_token_2022::ID {
+        return Ok(None);
+    }
+
+    let data = mint.try_borrow_data()?;
+    let state = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&data)?;
+
+    Ok(state.get_extension::<TransferFeeConfig>().ok().copied())
+}
+
+/// Fee withheld from the recipient when `amount` is transferred.
+pub fn transfer_fee(mint: &AccountInfo, amount: u64) -> Result<u64> {
+    match transfer_fee_config(mint)? {
+        Some(config) => config
+            .calculate_epoch_fee(Clock::get()?.epoch, amount)
+            .ok_or(error!(CustomError::MathOverflow)),
+        None => Ok(0),
+    }
+}
+
+/// Amount to send so that exactly `amount` is received once the transfer fee is withheld.
+pub fn amount_with_fee(mint: &AccountInfo, amount: u64) -> Result<u64> {
+    let fee = match transfer_fee_config(mint)? {
+        Some(config) => config
+            .calculate_inverse_epoch_fee(Clock::get()?.epoch, amount)
+            .ok_or(error!(CustomError::MathOverflow))?,
+        None => 0,
+    };
+
+    amount
+        .checked_add(fee)
+        .ok_or(error!(CustomError::MathOverflow))
+}
+
+/// Total fee withheld from the payouts of an escrow, zero for SOL escrows.
+/// Each recipient bears the fee on its own payout, the amounts sent are not topped up.
+pub fn withheld_fee(mint: Option<AccountInfo>, payouts: &[u64]) -> Result<u64> {
+    let mint = match mint {
+        Some(mint) => mint,
+        None => return Ok(0),
+    };
+
+    payouts.iter().try_fold(0u64, |total, payout| {
+        total
+            .checked_add(transfer_fee(&mint, *payout)?)
+            .ok_or(error!(CustomError::MathOverflow))
+    })
+}
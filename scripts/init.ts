@@ -16,7 +16,11 @@ import {
   ASSOCIATED_PROGRAM_ID,
   TOKEN_PROGRAM_ID,
 } from '@coral-xyz/anchor/dist/cjs/utils/token'
-import { getAssociatedTokenAddress } from '@solana/spl-token'
+import {
+  getAssociatedTokenAddress,
+  NATIVE_MINT,
+  TOKEN_2022_PROGRAM_ID,
+} from '@solana/spl-token'
 
 const { publicKey: programId } = Keypair.fromSecretKey(
   new Uint8Array(programKp),
@@ -42,6 +46,9 @@ const [programDataPda] = findProgramAddressSync(
 const [configPda] = findProgramAddressSync([Buffer.from('config')], programId)
 
 const init = async () => {
+  const endpoint = program.provider.connection.rpcEndpoint
+  const isLocalnet =
+    endpoint.includes('localhost') || endpoint.includes('127.0.0.1')
   const usdcPubkey = await getUsdc(program.provider.connection, authority)
   const existingConfig = await program.account.config.fetchNullable(configPda)
 
@@ -70,12 +77,19 @@ const init = async () => {
 
       await program.methods
         .initialize({
-          // USDC has 6 decimal places
-          clientFeeMin: new BN(2_000_000),
+          arbiter: authority.publicKey,
           // 100_00 as 100%
           clientFeePercentage: 3_00,
           freelancerFeePercentage: 5_00,
           referralFeePercentage: 5_00,
+          // TODO: switch to the governance token with update_config once it's minted
+          governanceMint: usdcPubkey,
+          // 3 days, a few seconds on localnet so tests can finalize disputes
+          disputeVoteDuration: new BN(isLocalnet ? 5 : 60 * 60 * 24 * 3),
+          // 5 tokens on localnet, 1M tokens otherwise (6 decimal places)
+          disputeQuorum: new BN(isLocalnet ? 5_000_000 : 1_000_000_000_000),
+          // 7 days, a few seconds on localnet so tests can auto-complete deals
+          reviewWindow: new BN(isLocalnet ? 5 : 60 * 60 * 24 * 7),
         })
         .accounts(accounts)
         .rpc()
@@ -86,6 +100,69 @@ const init = async () => {
       throw new Error(e)
     }
   }
+
+  const [collectionPda] = findProgramAddressSync(
+    [Buffer.from('credential_collection')],
+    programId,
+  )
+  const existingCollection = await program.provider.connection.getAccountInfo(
+    collectionPda,
+  )
+
+  if (!existingCollection) {
+    await program.methods
+      .createCollection()
+      .accounts({
+        collection: collectionPda,
+        config: configPda,
+        authority: authority.publicKey,
+        systemProgram: SystemProgram.programId,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+      })
+      .rpc()
+
+    console.log('Credential collection created')
+  }
+
+  // Minimum client fees are in the mint's own decimal places.
+  const mints = [
+    // USDC has 6 decimal places
+    { mint: usdcPubkey, clientFeeMin: new BN(2_000_000) },
+    // 0.01 SOL
+    { mint: NATIVE_MINT, clientFeeMin: new BN(10_000_000) },
+  ]
+
+  for (const { mint, clientFeeMin } of mints) {
+    const [mintConfigPda] = findProgramAddressSync(
+      [Buffer.from('mint'), mint.toBytes()],
+      programId,
+    )
+    const existingMintConfig = await program.account.mintConfig.fetchNullable(
+      mintConfigPda,
+    )
+
+    if (existingMintConfig) {
+      continue
+    }
+
+    await program.methods
+      .addMint({
+        clientFeeMin,
+        clientFeePercentage: null,
+        freelancerFeePercentage: null,
+        referralFeePercentage: null,
+      })
+      .accounts({
+        mintConfig: mintConfigPda,
+        mint,
+        config: configPda,
+        authority: authority.publicKey,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc()
+
+    console.log('Mint listed:', mint.toBase58())
+  }
 }
 
 init()
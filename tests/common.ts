@@ -1,10 +1,20 @@
 import {
   AnchorProvider,
+  BN,
   Program,
   setProvider,
   workspace,
 } from '@coral-xyz/anchor'
-import { Keypair } from '@solana/web3.js'
+import {
+  TOKEN_2022_PROGRAM_ID,
+  getAssociatedTokenAddressSync,
+} from '@solana/spl-token'
+import {
+  ComputeBudgetProgram,
+  Keypair,
+  PublicKey,
+  SystemProgram,
+} from '@solana/web3.js'
 import { Deezjobs } from '../target/types/deezjobs'
 import authorityKp from '../keys/authority.json'
 import treasuryKp from '../keys/treasury.json'
@@ -23,3 +33,75 @@ export const [configPda] = findProgramAddressSync(
   [Buffer.from('config')],
   program.programId,
 )
+
+export const getMintConfigPda = (mint: PublicKey) =>
+  findProgramAddressSync(
+    [Buffer.from('mint'), mint.toBytes()],
+    program.programId,
+  )[0]
+
+// Lists the mint for payments, deals can't be created with unlisted mints.
+export const listMint = async (mint: PublicKey, clientFeeMin = 0) => {
+  const mintConfigPda = getMintConfigPda(mint)
+  const existing = await program.account.mintConfig.fetchNullable(
+    mintConfigPda,
+  )
+
+  if (!existing) {
+    await program.methods
+      .addMint({
+        clientFeeMin: new BN(clientFeeMin),
+        clientFeePercentage: null,
+        freelancerFeePercentage: null,
+        referralFeePercentage: null,
+      })
+      .accounts({
+        mintConfig: mintConfigPda,
+        mint,
+        config: configPda,
+        authority: authority.publicKey,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([authority])
+      .rpc()
+  }
+
+  return mintConfigPda
+}
+
+export const getReceiptPda = (deal: PublicKey) =>
+  findProgramAddressSync(
+    [Buffer.from('receipt'), deal.toBytes()],
+    program.programId,
+  )[0]
+
+export const getProfilePda = (wallet: PublicKey) =>
+  findProgramAddressSync(
+    [Buffer.from('profile'), wallet.toBytes()],
+    program.programId,
+  )[0]
+
+export const getCredentialPda = (wallet: PublicKey) =>
+  findProgramAddressSync(
+    [Buffer.from('credential'), wallet.toBytes()],
+    program.programId,
+  )[0]
+
+// Token group every credential is a member of, created by scripts/init.ts.
+export const [credentialCollectionPda] = findProgramAddressSync(
+  [Buffer.from('credential_collection')],
+  program.programId,
+)
+
+export const getCredentialAccount = (wallet: PublicKey) =>
+  getAssociatedTokenAddressSync(
+    getCredentialPda(wallet),
+    wallet,
+    false,
+    TOKEN_2022_PROGRAM_ID,
+  )
+
+// Minting a credential along with its metadata exceeds the default compute limit.
+export const computeBudgetIx = ComputeBudgetProgram.setComputeUnitLimit({
+  units: 400_000,
+})
@@ -0,0 +1,399 @@
+import { AnchorError } from '@coral-xyz/anchor'
+import { findProgramAddressSync } from '@coral-xyz/anchor/dist/cjs/utils/pubkey'
+import { createMint, TOKEN_2022_PROGRAM_ID } from '@solana/spl-token'
+import { Keypair, SystemProgram } from '@solana/web3.js'
+import { BN } from 'bn.js'
+import { assert } from 'chai'
+import {
+  authority,
+  configPda,
+  credentialCollectionPda,
+  getMintConfigPda,
+  listMint,
+  program,
+  treasury,
+} from './common'
+
+describe('Config', () => {
+  it('should allow the authority to update the fees', async () => {
+    const config = await program.account.config.fetch(configPda)
+
+    await program.methods
+      .updateConfig({
+        clientFeePercentage: config.clientFeePercentage,
+        freelancerFeePercentage: config.freelancerFeePercentage,
+        referralFeePercentage: config.referralFeePercentage,
+        reviewWindow: config.reviewWindow,
+        governanceMint: config.governanceMint,
+        disputeVoteDuration: config.disputeVoteDuration,
+        disputeQuorum: config.disputeQuorum,
+      })
+      .accounts({
+        config: configPda,
+        treasury: treasury.publicKey,
+        authority: authority.publicKey,
+      })
+      .signers([authority])
+      .rpc()
+
+    const updated = await program.account.config.fetch(configPda)
+
+    assert.ok(updated.treasury.equals(treasury.publicKey))
+  })
+
+  it('should not allow the fees to exceed 100% of the offer', async () => {
+    const config = await program.account.config.fetch(configPda)
+
+    try {
+      await program.methods
+        .updateConfig({
+          clientFeePercentage: 3_00,
+          freelancerFeePercentage: 60_00,
+          referralFeePercentage: 50_00,
+          reviewWindow: new BN(5),
+          governanceMint: config.governanceMint,
+          disputeVoteDuration: config.disputeVoteDuration,
+          disputeQuorum: config.disputeQuorum,
+        })
+        .accounts({
+          config: configPda,
+          treasury: treasury.publicKey,
+          authority: authority.publicKey,
+        })
+        .signers([authority])
+        .rpc()
+
+      assert.ok(false)
+    } catch (e) {
+      const err = e as AnchorError
+
+      assert.ok(err.error.errorCode.code === 'InvalidFee')
+    }
+  })
+
+  it('should not allow disputes to be decided without a quorum', async () => {
+    const config = await program.account.config.fetch(configPda)
+
+    try {
+      await program.methods
+        .updateConfig({
+          clientFeePercentage: config.clientFeePercentage,
+          freelancerFeePercentage: config.freelancerFeePercentage,
+          referralFeePercentage: config.referralFeePercentage,
+          reviewWindow: config.reviewWindow,
+          governanceMint: config.governanceMint,
+          disputeVoteDuration: config.disputeVoteDuration,
+          disputeQuorum: new BN(0),
+        })
+        .accounts({
+          config: configPda,
+          treasury: treasury.publicKey,
+          authority: authority.publicKey,
+        })
+        .signers([authority])
+        .rpc()
+
+      assert.ok(false)
+    } catch (e) {
+      const err = e as AnchorError
+
+      assert.ok(err.error.errorCode.code === 'InvalidDisputeSettings')
+    }
+  })
+
+  it('should not allow deals to be completed without a review window', async () => {
+    const config = await program.account.config.fetch(configPda)
+
+    try {
+      await program.methods
+        .updateConfig({
+          clientFeePercentage: config.clientFeePercentage,
+          freelancerFeePercentage: config.freelancerFeePercentage,
+          referralFeePercentage: config.referralFeePercentage,
+          reviewWindow: new BN(0),
+          governanceMint: config.governanceMint,
+          disputeVoteDuration: config.disputeVoteDuration,
+          disputeQuorum: config.disputeQuorum,
+        })
+        .accounts({
+          config: configPda,
+          treasury: treasury.publicKey,
+          authority: authority.publicKey,
+        })
+        .signers([authority])
+        .rpc()
+
+      assert.ok(false)
+    } catch (e) {
+      const err = e as AnchorError
+
+      assert.ok(err.error.errorCode.code === 'InvalidReviewWindow')
+    }
+  })
+
+  it('should not allow anyone else to update the config', async () => {
+    const impostor = Keypair.generate()
+
+    try {
+      await program.methods
+        .updateConfig({
+          clientFeePercentage: 0,
+          freelancerFeePercentage: 0,
+          referralFeePercentage: 0,
+          reviewWindow: new BN(0),
+          governanceMint: impostor.publicKey,
+          disputeVoteDuration: new BN(0),
+          disputeQuorum: new BN(0),
+        })
+        .accounts({
+          config: configPda,
+          treasury: impostor.publicKey,
+          authority: impostor.publicKey,
+        })
+        .signers([impostor])
+        .rpc()
+
+      assert.ok(false)
+    } catch (e) {
+      const err = e as AnchorError
+
+      assert.ok(err.error.errorCode.code === 'Unauthorized')
+    }
+  })
+
+  it('should not allow anyone else to create the credential collection', async () => {
+    const impostor = Keypair.generate()
+
+    const collection = await program.provider.connection.getAccountInfo(
+      credentialCollectionPda,
+    )
+
+    assert.ok(collection.owner.equals(TOKEN_2022_PROGRAM_ID))
+
+    try {
+      await program.methods
+        .createCollection()
+        .accounts({
+          collection: credentialCollectionPda,
+          config: configPda,
+          authority: impostor.publicKey,
+          systemProgram: SystemProgram.programId,
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+        })
+        .signers([impostor])
+        .rpc()
+
+      assert.ok(false)
+    } catch (e) {
+      const err = e as AnchorError
+
+      assert.ok(err.error.errorCode.code === 'Unauthorized')
+    }
+  })
+
+  it('should let only the authority update or disable a listed mint', async () => {
+    // A fresh mint, the dummy USDC is shared with the other specs
+    const mint = await createMint(
+      program.provider.connection,
+      authority,
+      authority.publicKey,
+      null,
+      6,
+    )
+    const mintConfigPda = await listMint(mint, 2_000_000)
+    const impostor = Keypair.generate()
+
+    try {
+      await program.methods
+        .disableMint()
+        .accounts({
+          mintConfig: mintConfigPda,
+          config: configPda,
+          authority: impostor.publicKey,
+        })
+        .signers([impostor])
+        .rpc()
+
+      assert.ok(false)
+    } catch (e) {
+      const err = e as AnchorError
+
+      assert.ok(err.error.errorCode.code === 'Unauthorized')
+    }
+
+    await program.methods
+      .updateMint({
+        enabled: true,
+        clientFeeMin: new BN(1_000_000),
+        clientFeePercentage: 2_00,
+        freelancerFeePercentage: null,
+        referralFeePercentage: null,
+      })
+      .accounts({
+        mintConfig: mintConfigPda,
+        config: configPda,
+        authority: authority.publicKey,
+      })
+      .signers([authority])
+      .rpc()
+
+    let mintConfig = await program.account.mintConfig.fetch(
+      getMintConfigPda(mint),
+    )
+
+    assert.ok(mintConfig.clientFeeMin.eq(new BN(1_000_000)))
+    assert.ok(mintConfig.clientFeePercentage === 2_00)
+
+    await program.methods
+      .disableMint()
+      .accounts({
+        mintConfig: mintConfigPda,
+        config: configPda,
+        authority: authority.publicKey,
+      })
+      .signers([authority])
+      .rpc()
+
+    mintConfig = await program.account.mintConfig.fetch(mintConfigPda)
+
+    assert.ok(!mintConfig.enabled)
+  })
+
+  it('should transfer the authority only once the new key accepts', async () => {
+    const newAuthority = Keypair.generate()
+    const impostor = Keypair.generate()
+
+    await program.methods
+      .proposeAuthority()
+      .accounts({
+        config: configPda,
+        newAuthority: newAuthority.publicKey,
+        authority: authority.publicKey,
+      })
+      .signers([authority])
+      .rpc()
+
+    let config = await program.account.config.fetch(configPda)
+
+    assert.ok(config.authority.equals(authority.publicKey))
+    assert.ok(config.pendingAuthority.equals(newAuthority.publicKey))
+
+    try {
+      await program.methods
+        .acceptAuthority()
+        .accounts({
+          config: configPda,
+          newAuthority: impostor.publicKey,
+        })
+        .signers([impostor])
+        .rpc()
+
+      assert.ok(false)
+    } catch (e) {
+      const err = e as AnchorError
+
+      assert.ok(err.error.errorCode.code === 'Unauthorized')
+    }
+
+    await program.methods
+      .acceptAuthority()
+      .accounts({
+        config: configPda,
+        newAuthority: newAuthority.publicKey,
+      })
+      .signers([newAuthority])
+      .rpc()
+
+    config = await program.account.config.fetch(configPda)
+
+    assert.ok(config.authority.equals(newAuthority.publicKey))
+    assert.ok(config.pendingAuthority === null)
+
+    // Hand it back for the rest of the tests
+
+    await program.methods
+      .proposeAuthority()
+      .accounts({
+        config: configPda,
+        newAuthority: authority.publicKey,
+        authority: newAuthority.publicKey,
+      })
+      .signers([newAuthority])
+      .rpc()
+
+    await program.methods
+      .acceptAuthority()
+      .accounts({
+        config: configPda,
+        newAuthority: authority.publicKey,
+      })
+      .signers([authority])
+      .rpc()
+
+    config = await program.account.config.fetch(configPda)
+
+    assert.ok(config.authority.equals(authority.publicKey))
+  })
+  it('should reject new gigs while create_gig is paused', async () => {
+    const gigId = Keypair.generate()
+    const [gigPda] = findProgramAddressSync(
+      [
+        Buffer.from('gig'),
+        authority.publicKey.toBytes(),
+        gigId.publicKey.toBytes().slice(0, 8),
+      ],
+      program.programId,
+    )
+
+    await program.methods
+      .setPause({ paused: 1 })
+      .accounts({
+        config: configPda,
+        authority: authority.publicKey,
+      })
+      .signers([authority])
+      .rpc()
+
+    try {
+      await program.methods
+        .createGig({
+          asking: new BN(100_000_000),
+          category: 0,
+          skills: new BN(0),
+          minCompletionTime: new BN(60 * 24),
+          isPublished: true,
+          acceptsReferrals: true,
+        })
+        .accounts({
+          id: gigId.publicKey,
+          config: configPda,
+          gig: gigPda,
+          mint: null,
+          owner: authority.publicKey,
+          payer: authority.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([authority, gigId])
+        .rpc()
+
+      assert.ok(false)
+    } catch (e) {
+      const err = e as AnchorError
+
+      assert.ok(err.error.errorCode.code === 'Paused')
+    }
+
+    await program.methods
+      .setPause({ paused: 0 })
+      .accounts({
+        config: configPda,
+        authority: authority.publicKey,
+      })
+      .signers([authority])
+      .rpc()
+
+    const config = await program.account.config.fetch(configPda)
+
+    assert.ok(config.paused === 0)
+  })
+})
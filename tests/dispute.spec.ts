@@ -0,0 +1,676 @@
+import { AnchorError } from '@coral-xyz/anchor'
+import { findProgramAddressSync } from '@coral-xyz/anchor/dist/cjs/utils/pubkey'
+import {
+  ASSOCIATED_TOKEN_PROGRAM_ID,
+  TOKEN_2022_PROGRAM_ID,
+  TOKEN_PROGRAM_ID,
+} from '@solana/spl-token'
+import {
+  Keypair,
+  LAMPORTS_PER_SOL,
+  PublicKey,
+  SystemProgram,
+  SYSVAR_RENT_PUBKEY,
+} from '@solana/web3.js'
+import { BN } from 'bn.js'
+import { assert } from 'chai'
+import { TokenMint } from '../scripts/utils'
+import {
+  authority,
+  computeBudgetIx,
+  configPda,
+  credentialCollectionPda,
+  getCredentialAccount,
+  getCredentialPda,
+  getProfilePda,
+  getReceiptPda,
+  listMint,
+  program,
+  treasury,
+  wait,
+} from './common'
+
+let freelancer = Keypair.generate()
+let client = Keypair.generate()
+let voter = Keypair.generate()
+let gigId = Keypair.generate()
+let gigNonce = gigId.publicKey.toBytes().slice(0, 8)
+let [gigPda] = findProgramAddressSync(
+  [Buffer.from('gig'), freelancer.publicKey.toBytes(), gigNonce],
+  program.programId,
+)
+let dealId = Keypair.generate()
+let dealNonce = dealId.publicKey.toBytes().slice(0, 8)
+let [dealPda] = findProgramAddressSync(
+  [
+    Buffer.from('deal'),
+    client.publicKey.toBytes(),
+    gigPda.toBytes(),
+    dealNonce,
+  ],
+  program.programId,
+)
+let [receiptPda] = findProgramAddressSync(
+  [Buffer.from('receipt'), dealPda.toBytes()],
+  program.programId,
+)
+let [disputePda] = findProgramAddressSync(
+  [Buffer.from('dispute'), dealPda.toBytes()],
+  program.programId,
+)
+let [votePda] = findProgramAddressSync(
+  [Buffer.from('vote'), disputePda.toBytes(), voter.publicKey.toBytes()],
+  program.programId,
+)
+let dealEscrowPda: PublicKey
+let voteVaultPda: PublicKey
+let voterUsdc: PublicKey
+let freelancerUsdc: PublicKey
+let clientUsdc: PublicKey
+let treasuryUsdc: PublicKey
+let usdc: TokenMint
+let mintConfigPda: PublicKey
+
+describe('Dispute resolution', () => {
+  const offer = new BN(100_000_000)
+  const deadline = new BN(new Date().getTime() / 1000 + 60 * 24 * 2)
+
+  before(async () => {
+    const airdropAmount = 2 * LAMPORTS_PER_SOL
+
+    await program.provider.connection.requestAirdrop(
+      freelancer.publicKey,
+      airdropAmount,
+    )
+
+    await wait(500)
+
+    await program.provider.connection.requestAirdrop(
+      client.publicKey,
+      airdropAmount,
+    )
+
+    await wait(500)
+
+    await program.provider.connection.requestAirdrop(
+      voter.publicKey,
+      airdropAmount,
+    )
+
+    await wait(500)
+
+    usdc = await TokenMint.init(
+      program.provider.connection,
+      authority,
+      authority,
+    )
+
+    mintConfigPda = await listMint(usdc.token, 2_000_000)
+
+    clientUsdc = await usdc.getAssociatedTokenAccount(client.publicKey)
+    freelancerUsdc = await usdc.getAssociatedTokenAccount(freelancer.publicKey)
+    treasuryUsdc = await usdc.getAssociatedTokenAccount(treasury.publicKey)
+    dealEscrowPda = await usdc.getAssociatedTokenAccount(dealPda, true)
+
+    // The dummy USDC doubles as the governance token on localnet
+    voterUsdc = await usdc.getAssociatedTokenAccount(voter.publicKey)
+    voteVaultPda = await usdc.getAssociatedTokenAccount(votePda, true)
+
+    await usdc.mintInto(clientUsdc, 1_000_000_000)
+    await usdc.mintInto(voterUsdc, 10_000_000)
+
+    await program.methods
+      .createGig({
+        asking: offer,
+        category: 0,
+        skills: new BN(0),
+        minCompletionTime: new BN(60 * 24),
+        isPublished: true,
+        acceptsReferrals: true,
+      })
+      .accounts({
+        id: gigId.publicKey,
+        config: configPda,
+        gig: gigPda,
+        mint: usdc.token,
+        owner: freelancer.publicKey,
+        payer: freelancer.publicKey,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([freelancer, gigId])
+      .rpc()
+
+    await program.methods
+      .createDeal({
+        offer,
+        deadline,
+        milestones: [],
+      })
+      .accounts({
+        config: configPda,
+        mintConfig: mintConfigPda,
+        referrer: null,
+        deal: dealPda,
+        receipt: getReceiptPda(dealPda),
+        id: dealId.publicKey,
+        gig: gigPda,
+        mint: usdc.token,
+        owner: client.publicKey,
+        ownerWallet: clientUsdc,
+        escrow: dealEscrowPda,
+        systemProgram: SystemProgram.programId,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        rent: SYSVAR_RENT_PUBKEY,
+      })
+      .signers([client, dealId])
+      .rpc()
+
+    await program.methods
+      .acceptDeal({ expectedOffer: offer, expectedDeadline: deadline })
+      .accounts({
+        deal: dealPda,
+        freelancer: freelancer.publicKey,
+        gig: gigPda,
+        config: configPda,
+      })
+      .signers([freelancer])
+      .rpc()
+  })
+
+  it('should not allow anyone else to raise a dispute', async () => {
+    try {
+      await program.methods
+        .raiseDispute({
+          reason: 0,
+          evidence: Array(32).fill(0),
+        })
+        .accounts({
+          dispute: disputePda,
+          deal: dealPda,
+          signer: voter.publicKey,
+          config: configPda,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([voter])
+        .rpc()
+
+      assert.ok(false)
+    } catch (e) {
+      const err = e as AnchorError
+
+      assert.ok(err.error.errorCode.code === 'Unauthorized')
+    }
+  })
+
+  it('should allow the freelancer to raise a dispute', async () => {
+    await program.methods
+      .raiseDispute({
+        reason: 4,
+        evidence: Array(32).fill(1),
+      })
+      .accounts({
+        dispute: disputePda,
+        deal: dealPda,
+        signer: freelancer.publicKey,
+        config: configPda,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([freelancer])
+      .rpc()
+
+    const deal = await program.account.deal.fetch(dealPda)
+    const dispute = await program.account.dispute.fetch(disputePda)
+
+    assert.ok('disputed' in deal.state)
+    assert.ok(dispute.openedBy.equals(freelancer.publicKey))
+    assert.ok(dispute.reason === 4)
+  })
+
+  it('should not allow the client to complete a disputed deal', async () => {
+    try {
+      await program.methods
+        .completeDeal()
+        .accounts({
+          client: client.publicKey,
+          deal: dealPda,
+          receipt: receiptPda,
+          escrow: dealEscrowPda,
+          gig: gigPda,
+          mint: usdc.token,
+          signer: client.publicKey,
+          referrer: null,
+          referrerTokenAccount: null,
+          config: configPda,
+          freelancerProfile: getProfilePda(freelancer.publicKey),
+          freelancerCredential: getCredentialPda(freelancer.publicKey),
+          freelancerCredentialAccount: getCredentialAccount(
+            freelancer.publicKey,
+          ),
+          clientProfile: getProfilePda(client.publicKey),
+          clientCredential: getCredentialPda(client.publicKey),
+          clientCredentialAccount: getCredentialAccount(client.publicKey),
+          credentialCollection: credentialCollectionPda,
+          freelancer: freelancer.publicKey,
+          freelancerTokenAccount: freelancerUsdc,
+          treasury: treasury.publicKey,
+          treasuryTokenAccount: treasuryUsdc,
+          systemProgram: SystemProgram.programId,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          token2022Program: TOKEN_2022_PROGRAM_ID,
+          rent: SYSVAR_RENT_PUBKEY,
+        })
+        .preInstructions([computeBudgetIx])
+        .signers([client])
+        .rpc()
+
+      assert.ok(false)
+    } catch (e) {
+      const err = e as AnchorError
+
+      assert.ok(err.error.errorCode.code === 'DealHasDispute')
+    }
+  })
+
+  it('should not allow the client to close a disputed deal', async () => {
+    try {
+      await program.methods
+        .closeDeal()
+        .accounts({
+          client: client.publicKey,
+          deal: dealPda,
+          escrow: dealEscrowPda,
+          gig: gigPda,
+          mint: usdc.token,
+          ownerWallet: clientUsdc,
+          signer: client.publicKey,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([client])
+        .rpc()
+
+      assert.ok(false)
+    } catch (e) {
+      const err = e as AnchorError
+
+      assert.ok(err.error.errorCode.code === 'DealHasDispute')
+    }
+  })
+
+  it('should not allow the authority to settle while voting is open', async () => {
+    try {
+      await program.methods
+        .settleDispute({
+          clientShare: 100_00,
+        })
+        .accounts({
+          gig: gigPda,
+          deal: dealPda,
+          dispute: disputePda,
+          openedBy: freelancer.publicKey,
+          freelancer: freelancer.publicKey,
+          client: client.publicKey,
+          mint: usdc.token,
+          escrow: dealEscrowPda,
+          clientTokenAccount: clientUsdc,
+          freelancerTokenAccount: freelancerUsdc,
+          treasury: treasury.publicKey,
+          treasuryTokenAccount: treasuryUsdc,
+          config: configPda,
+          freelancerProfile: getProfilePda(freelancer.publicKey),
+          freelancerCredential: getCredentialPda(freelancer.publicKey),
+          freelancerCredentialAccount: getCredentialAccount(
+            freelancer.publicKey,
+          ),
+          clientProfile: getProfilePda(client.publicKey),
+          clientCredential: getCredentialPda(client.publicKey),
+          clientCredentialAccount: getCredentialAccount(client.publicKey),
+          credentialCollection: credentialCollectionPda,
+          signer: authority.publicKey,
+          systemProgram: SystemProgram.programId,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          token2022Program: TOKEN_2022_PROGRAM_ID,
+          rent: SYSVAR_RENT_PUBKEY,
+        })
+        .preInstructions([computeBudgetIx])
+        .signers([authority])
+        .rpc()
+
+      assert.ok(false)
+    } catch (e) {
+      const err = e as AnchorError
+
+      assert.ok(err.error.errorCode.code === 'VotingInProgress')
+    }
+  })
+
+  it('should not allow the client to vote on their own dispute', async () => {
+    const [clientVotePda] = findProgramAddressSync(
+      [Buffer.from('vote'), disputePda.toBytes(), client.publicKey.toBytes()],
+      program.programId,
+    )
+
+    try {
+      await program.methods
+        .castVote({
+          clientShare: 100_00,
+          weight: new BN(1_000_000),
+        })
+        .accounts({
+          vote: clientVotePda,
+          voteVault: await usdc.getAssociatedTokenAccount(clientVotePda, true),
+          voterWallet: clientUsdc,
+          dispute: disputePda,
+          deal: dealPda,
+          governanceMint: usdc.token,
+          config: configPda,
+          voter: client.publicKey,
+          systemProgram: SystemProgram.programId,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          rent: SYSVAR_RENT_PUBKEY,
+        })
+        .signers([client])
+        .rpc()
+
+      assert.ok(false)
+    } catch (e) {
+      const err = e as AnchorError
+
+      assert.ok(err.error.errorCode.code === 'PartyCannotVote')
+    }
+  })
+
+  it('should allow a token holder to vote on the dispute', async () => {
+    await program.methods
+      .castVote({
+        clientShare: 50_00,
+        weight: new BN(10_000_000),
+      })
+      .accounts({
+        vote: votePda,
+        voteVault: voteVaultPda,
+        voterWallet: voterUsdc,
+        dispute: disputePda,
+        deal: dealPda,
+        governanceMint: usdc.token,
+        config: configPda,
+        voter: voter.publicKey,
+        systemProgram: SystemProgram.programId,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        rent: SYSVAR_RENT_PUBKEY,
+      })
+      .signers([voter])
+      .rpc()
+
+    const dispute = await program.account.dispute.fetch(disputePda)
+
+    const vaultBalance =
+      await program.provider.connection.getTokenAccountBalance(voteVaultPda)
+
+    assert.ok(dispute.totalWeight.eq(new BN(10_000_000)))
+    assert.ok(vaultBalance.value.uiAmountString === '10')
+  })
+
+  it('should allow anyone to finalize the dispute once voting closes', async () => {
+    // Voting window is a few seconds on localnet
+    await wait(6000)
+
+    await program.methods
+      .finalizeDispute()
+      .accounts({
+        gig: gigPda,
+        deal: dealPda,
+        dispute: disputePda,
+        openedBy: freelancer.publicKey,
+        freelancer: freelancer.publicKey,
+        client: client.publicKey,
+        mint: usdc.token,
+        escrow: dealEscrowPda,
+        clientTokenAccount: clientUsdc,
+        freelancerTokenAccount: freelancerUsdc,
+        treasury: treasury.publicKey,
+        treasuryTokenAccount: treasuryUsdc,
+        config: configPda,
+        freelancerProfile: getProfilePda(freelancer.publicKey),
+        freelancerCredential: getCredentialPda(freelancer.publicKey),
+        freelancerCredentialAccount: getCredentialAccount(freelancer.publicKey),
+        clientProfile: getProfilePda(client.publicKey),
+        clientCredential: getCredentialPda(client.publicKey),
+        clientCredentialAccount: getCredentialAccount(client.publicKey),
+        credentialCollection: credentialCollectionPda,
+        signer: voter.publicKey,
+        systemProgram: SystemProgram.programId,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        token2022Program: TOKEN_2022_PROGRAM_ID,
+        rent: SYSVAR_RENT_PUBKEY,
+      })
+      .preInstructions([computeBudgetIx])
+      .signers([voter])
+      .rpc()
+
+    const deal = await program.account.deal.fetchNullable(dealPda)
+    const dispute = await program.account.dispute.fetchNullable(disputePda)
+
+    const dealEscrow = await program.provider.connection.getAccountInfo(
+      dealEscrowPda,
+    )
+
+    const clientBalance =
+      await program.provider.connection.getTokenAccountBalance(clientUsdc)
+
+    const freelancerBalance =
+      await program.provider.connection.getTokenAccountBalance(freelancerUsdc)
+
+    assert.ok(deal === null)
+    assert.ok(dispute === null)
+    assert.ok(dealEscrow === null)
+    assert.ok(clientBalance.value.uiAmountString === '950')
+    assert.ok(freelancerBalance.value.uiAmountString === '47.5')
+
+    const freelancerProfile = await program.account.profile.fetch(
+      getProfilePda(freelancer.publicKey),
+    )
+
+    // An even split has no winner
+    assert.ok(freelancerProfile.disputesWon === 0)
+    assert.ok(freelancerProfile.disputesLost === 0)
+    assert.ok(freelancerProfile.volumes[0].amount.eq(new BN(50_000_000)))
+  })
+
+  it('should allow the voter to withdraw the locked tokens', async () => {
+    await program.methods
+      .withdrawVote()
+      .accounts({
+        vote: votePda,
+        voteVault: voteVaultPda,
+        voterWallet: voterUsdc,
+        dispute: disputePda,
+        governanceMint: usdc.token,
+        voter: voter.publicKey,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([voter])
+      .rpc()
+
+    const vote = await program.account.disputeVote.fetchNullable(votePda)
+
+    const voterBalance =
+      await program.provider.connection.getTokenAccountBalance(voterUsdc)
+
+    assert.ok(vote === null)
+    assert.ok(voterBalance.value.uiAmountString === '10')
+  })
+
+  it('should let the authority settle a dispute that missed the quorum', async () => {
+    const lowDealId = Keypair.generate()
+    const [lowDealPda] = findProgramAddressSync(
+      [
+        Buffer.from('deal'),
+        client.publicKey.toBytes(),
+        gigPda.toBytes(),
+        lowDealId.publicKey.toBytes().slice(0, 8),
+      ],
+      program.programId,
+    )
+    const [lowDisputePda] = findProgramAddressSync(
+      [Buffer.from('dispute'), lowDealPda.toBytes()],
+      program.programId,
+    )
+    const [lowVotePda] = findProgramAddressSync(
+      [Buffer.from('vote'), lowDisputePda.toBytes(), voter.publicKey.toBytes()],
+      program.programId,
+    )
+    const lowEscrowPda = await usdc.getAssociatedTokenAccount(lowDealPda, true)
+
+    await program.methods
+      .createDeal({
+        offer,
+        deadline,
+        milestones: [],
+      })
+      .accounts({
+        config: configPda,
+        mintConfig: mintConfigPda,
+        referrer: null,
+        deal: lowDealPda,
+        receipt: getReceiptPda(lowDealPda),
+        id: lowDealId.publicKey,
+        gig: gigPda,
+        mint: usdc.token,
+        owner: client.publicKey,
+        ownerWallet: clientUsdc,
+        escrow: lowEscrowPda,
+        systemProgram: SystemProgram.programId,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        rent: SYSVAR_RENT_PUBKEY,
+      })
+      .signers([client, lowDealId])
+      .rpc()
+
+    await program.methods
+      .acceptDeal({ expectedOffer: offer, expectedDeadline: deadline })
+      .accounts({
+        deal: lowDealPda,
+        freelancer: freelancer.publicKey,
+        gig: gigPda,
+        config: configPda,
+      })
+      .signers([freelancer])
+      .rpc()
+
+    await program.methods
+      .raiseDispute({
+        reason: 1,
+        evidence: Array(32).fill(2),
+      })
+      .accounts({
+        dispute: lowDisputePda,
+        deal: lowDealPda,
+        signer: client.publicKey,
+        config: configPda,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([client])
+      .rpc()
+
+    // A single base unit is far below the quorum
+    await program.methods
+      .castVote({
+        clientShare: 0,
+        weight: new BN(1),
+      })
+      .accounts({
+        vote: lowVotePda,
+        voteVault: await usdc.getAssociatedTokenAccount(lowVotePda, true),
+        voterWallet: voterUsdc,
+        dispute: lowDisputePda,
+        deal: lowDealPda,
+        governanceMint: usdc.token,
+        config: configPda,
+        voter: voter.publicKey,
+        systemProgram: SystemProgram.programId,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        rent: SYSVAR_RENT_PUBKEY,
+      })
+      .signers([voter])
+      .rpc()
+
+    await wait(6000)
+
+    const accounts = {
+      gig: gigPda,
+      deal: lowDealPda,
+      dispute: lowDisputePda,
+      openedBy: client.publicKey,
+      freelancer: freelancer.publicKey,
+      client: client.publicKey,
+      mint: usdc.token,
+      escrow: lowEscrowPda,
+      clientTokenAccount: clientUsdc,
+      freelancerTokenAccount: freelancerUsdc,
+      treasury: treasury.publicKey,
+      treasuryTokenAccount: treasuryUsdc,
+      config: configPda,
+      freelancerProfile: getProfilePda(freelancer.publicKey),
+      freelancerCredential: getCredentialPda(freelancer.publicKey),
+      freelancerCredentialAccount: getCredentialAccount(freelancer.publicKey),
+      clientProfile: getProfilePda(client.publicKey),
+      clientCredential: getCredentialPda(client.publicKey),
+      clientCredentialAccount: getCredentialAccount(client.publicKey),
+      credentialCollection: credentialCollectionPda,
+      systemProgram: SystemProgram.programId,
+      associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+      tokenProgram: TOKEN_PROGRAM_ID,
+      token2022Program: TOKEN_2022_PROGRAM_ID,
+      rent: SYSVAR_RENT_PUBKEY,
+    }
+
+    try {
+      await program.methods
+        .finalizeDispute()
+        .accounts({ ...accounts, signer: voter.publicKey })
+        .preInstructions([computeBudgetIx])
+        .signers([voter])
+        .rpc()
+
+      assert.ok(false)
+    } catch (e) {
+      const err = e as AnchorError
+
+      assert.ok(err.error.errorCode.code === 'QuorumNotReached')
+    }
+
+    const clientBefore =
+      await program.provider.connection.getTokenAccountBalance(clientUsdc)
+
+    await program.methods
+      .settleDispute({
+        clientShare: 100_00,
+      })
+      .accounts({ ...accounts, signer: authority.publicKey })
+      .preInstructions([computeBudgetIx])
+      .signers([authority])
+      .rpc()
+
+    const deal = await program.account.deal.fetchNullable(lowDealPda)
+    const dispute = await program.account.dispute.fetchNullable(lowDisputePda)
+
+    const clientAfter =
+      await program.provider.connection.getTokenAccountBalance(clientUsdc)
+
+    assert.ok(deal === null)
+    assert.ok(dispute === null)
+    // The whole offer and the client fee are refunded
+    assert.ok(
+      new BN(clientAfter.value.amount)
+        .sub(new BN(clientBefore.value.amount))
+        .eq(new BN(103_000_000)),
+    )
+  })
+})
@@ -2,6 +2,8 @@ import { AnchorError } from '@coral-xyz/anchor'
 import { findProgramAddressSync } from '@coral-xyz/anchor/dist/cjs/utils/pubkey'
 import {
   ASSOCIATED_TOKEN_PROGRAM_ID,
+  getAssociatedTokenAddressSync,
+  TOKEN_2022_PROGRAM_ID,
   TOKEN_PROGRAM_ID,
 } from '@solana/spl-token'
 import {
@@ -14,7 +16,20 @@ import {
 import { BN } from 'bn.js'
 import { assert } from 'chai'
 import { TokenMint } from '../scripts/utils'
-import { authority, configPda, program, treasury, wait } from './common'
+import {
+  authority,
+  computeBudgetIx,
+  configPda,
+  credentialCollectionPda,
+  getCredentialAccount,
+  getCredentialPda,
+  getProfilePda,
+  getReceiptPda,
+  listMint,
+  program,
+  treasury,
+  wait,
+} from './common'
 
 let freelancer = Keypair.generate()
 let client = Keypair.generate()
@@ -24,8 +39,19 @@ let [gigPda] = findProgramAddressSync(
   [Buffer.from('gig'), freelancer.publicKey.toBytes(), gigNonce],
   program.programId,
 )
+let dealId = Keypair.generate()
+let dealNonce = dealId.publicKey.toBytes().slice(0, 8)
 let [dealPda] = findProgramAddressSync(
-  [Buffer.from('deal'), client.publicKey.toBytes(), gigPda.toBytes()],
+  [
+    Buffer.from('deal'),
+    client.publicKey.toBytes(),
+    gigPda.toBytes(),
+    dealNonce,
+  ],
+  program.programId,
+)
+let [receiptPda] = findProgramAddressSync(
+  [Buffer.from('receipt'), dealPda.toBytes()],
   program.programId,
 )
 let dealEscrowPda: PublicKey
@@ -33,6 +59,7 @@ let freelancerUsdc: PublicKey
 let clientUsdc: PublicKey
 let treasuryUsdc: PublicKey
 let usdc: TokenMint
+let mintConfigPda: PublicKey
 
 describe('Gig & Deal interaction', () => {
   const offer = new BN(100_000_000)
@@ -61,6 +88,8 @@ describe('Gig & Deal interaction', () => {
       authority,
     )
 
+    mintConfigPda = await listMint(usdc.token, 2_000_000)
+
     clientUsdc = await usdc.getAssociatedTokenAccount(client.publicKey)
     freelancerUsdc = await usdc.getAssociatedTokenAccount(freelancer.publicKey)
     treasuryUsdc = await usdc.getAssociatedTokenAccount(treasury.publicKey)
@@ -79,9 +108,12 @@ describe('Gig & Deal interaction', () => {
         category: 0,
         skills: new BN(0),
         minCompletionTime,
+        isPublished: true,
+        acceptsReferrals: true,
       })
       .accounts({
         id: gigId.publicKey,
+        config: configPda,
         gig: gigPda,
         mint: usdc.token,
         owner: freelancer.publicKey,
@@ -97,16 +129,191 @@ describe('Gig & Deal interaction', () => {
     assert.ok(gig.minCompletionTime.eq(minCompletionTime))
   })
 
+  it('should not allow offers while the gig is paused', async () => {
+    await program.methods
+      .setGigState({ state: { paused: {} } })
+      .accounts({
+        gig: gigPda,
+        owner: freelancer.publicKey,
+      })
+      .signers([freelancer])
+      .rpc()
+
+    try {
+      await program.methods
+        .createDeal({
+          offer,
+          deadline,
+          milestones: [],
+        })
+        .accounts({
+          config: configPda,
+          mintConfig: mintConfigPda,
+          referrer: null,
+          deal: dealPda,
+          receipt: getReceiptPda(dealPda),
+          id: dealId.publicKey,
+          gig: gigPda,
+          mint: usdc.token,
+          owner: client.publicKey,
+          ownerWallet: clientUsdc,
+          escrow: dealEscrowPda,
+          systemProgram: SystemProgram.programId,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          rent: SYSVAR_RENT_PUBKEY,
+        })
+        .signers([client, dealId])
+        .rpc()
+
+      assert.ok(false)
+    } catch (e) {
+      const err = e as AnchorError
+
+      assert.ok(err.error.errorCode.code === 'InvalidGigState')
+    }
+
+    await program.methods
+      .setGigState({ state: { published: {} } })
+      .accounts({
+        gig: gigPda,
+        owner: freelancer.publicKey,
+      })
+      .signers([freelancer])
+      .rpc()
+
+    const gig = await program.account.gig.fetch(gigPda)
+
+    assert.ok('published' in gig.state)
+  })
+
+  it('should not allow a published gig to go back to draft', async () => {
+    try {
+      await program.methods
+        .setGigState({ state: { draft: {} } })
+        .accounts({
+          gig: gigPda,
+          owner: freelancer.publicKey,
+        })
+        .signers([freelancer])
+        .rpc()
+
+      assert.ok(false)
+    } catch (e) {
+      const err = e as AnchorError
+
+      assert.ok(err.error.errorCode.code === 'InvalidGigTransition')
+    }
+  })
+
+  it('should not allow the freelancer to refer their own gig', async () => {
+    const [referrerPda] = findProgramAddressSync(
+      [Buffer.from('referrer'), freelancer.publicKey.toBytes()],
+      program.programId,
+    )
+
+    await program.methods
+      .registerReferrer()
+      .accounts({
+        referrer: referrerPda,
+        owner: freelancer.publicKey,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([freelancer])
+      .rpc()
+
+    try {
+      await program.methods
+        .createDeal({
+          offer,
+          deadline,
+          milestones: [],
+        })
+        .accounts({
+          config: configPda,
+          mintConfig: mintConfigPda,
+          referrer: referrerPda,
+          deal: dealPda,
+          receipt: getReceiptPda(dealPda),
+          id: dealId.publicKey,
+          gig: gigPda,
+          mint: usdc.token,
+          owner: client.publicKey,
+          ownerWallet: clientUsdc,
+          escrow: dealEscrowPda,
+          systemProgram: SystemProgram.programId,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          rent: SYSVAR_RENT_PUBKEY,
+        })
+        .signers([client, dealId])
+        .rpc()
+
+      assert.ok(false)
+    } catch (e) {
+      const err = e as AnchorError
+
+      assert.ok(err.error.errorCode.code === 'InvalidReferrer')
+    }
+  })
+
+  it('should not allow the freelancer to make a deal on their own gig', async () => {
+    const [selfDealPda] = findProgramAddressSync(
+      [
+        Buffer.from('deal'),
+        freelancer.publicKey.toBytes(),
+        gigPda.toBytes(),
+        dealNonce,
+      ],
+      program.programId,
+    )
+
+    try {
+      await program.methods
+        .createDeal({
+          offer,
+          deadline,
+          milestones: [],
+        })
+        .accounts({
+          config: configPda,
+          mintConfig: mintConfigPda,
+          referrer: null,
+          deal: selfDealPda,
+          receipt: getReceiptPda(selfDealPda),
+          id: dealId.publicKey,
+          gig: gigPda,
+          mint: usdc.token,
+          owner: freelancer.publicKey,
+          ownerWallet: freelancerUsdc,
+          escrow: getAssociatedTokenAddressSync(usdc.token, selfDealPda, true),
+          systemProgram: SystemProgram.programId,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          rent: SYSVAR_RENT_PUBKEY,
+        })
+        .signers([freelancer, dealId])
+        .rpc()
+
+      assert.ok(false)
+    } catch (e) {
+      const err = e as AnchorError
+
+      assert.ok(err.error.errorCode.code === 'SelfDeal')
+    }
+  })
+
   it('should allow the client to offer a deal', async () => {
     await program.methods
       .createDeal({
         offer,
         deadline,
-        referrer: null,
+        milestones: [],
       })
       .accounts({
         config: configPda,
+        mintConfig: mintConfigPda,
+        referrer: null,
         deal: dealPda,
+        receipt: getReceiptPda(dealPda),
+        id: dealId.publicKey,
         gig: gigPda,
         mint: usdc.token,
         owner: client.publicKey,
@@ -116,7 +323,7 @@ describe('Gig & Deal interaction', () => {
         tokenProgram: TOKEN_PROGRAM_ID,
         rent: SYSVAR_RENT_PUBKEY,
       })
-      .signers([client])
+      .signers([client, dealId])
       .rpc()
 
     const deal = await program.account.deal.fetch(dealPda)
@@ -168,11 +375,15 @@ describe('Gig & Deal interaction', () => {
       .createDeal({
         offer,
         deadline,
-        referrer: null,
+        milestones: [],
       })
       .accounts({
         config: configPda,
+        mintConfig: mintConfigPda,
+        referrer: null,
         deal: dealPda,
+        receipt: getReceiptPda(dealPda),
+        id: dealId.publicKey,
         gig: gigPda,
         mint: usdc.token,
         owner: client.publicKey,
@@ -182,7 +393,7 @@ describe('Gig & Deal interaction', () => {
         tokenProgram: TOKEN_PROGRAM_ID,
         rent: SYSVAR_RENT_PUBKEY,
       })
-      .signers([client])
+      .signers([client, dealId])
       .rpc()
 
     const deal = await program.account.deal.fetch(dealPda)
@@ -195,24 +406,55 @@ describe('Gig & Deal interaction', () => {
 
     assert.ok(deal.offer.eq(offer))
     assert.ok(deal.deadline.eq(deadline))
+    assert.ok(deal.clientFeePercentage === 3_00)
+    assert.ok(deal.freelancerFeePercentage === 5_00)
+    assert.ok(deal.referralFeePercentage === 5_00)
     assert.ok(escrowBalance.value.uiAmountString === '103')
     assert.ok(clientBalance.value.uiAmountString === '897')
   })
 
   it('should allow the freelancer to accept a deal', async () => {
     await program.methods
-      .acceptDeal()
+      .acceptDeal({ expectedOffer: offer, expectedDeadline: deadline })
       .accounts({
         deal: dealPda,
         freelancer: freelancer.publicKey,
         gig: gigPda,
+        config: configPda,
       })
       .signers([freelancer])
       .rpc()
 
     const deal = await program.account.deal.fetch(dealPda)
 
-    assert.ok((deal.state & 2) === 2)
+    assert.ok('accepted' in deal.state)
+  })
+
+  it('should allow the freelancer to update the gig', async () => {
+    const asking = new BN(200_000_000)
+    const minCompletionTime = new BN(60 * 24 * 3)
+
+    await program.methods
+      .updateGig({
+        asking,
+        category: 1,
+        skills: new BN(3),
+        minCompletionTime,
+        acceptsReferrals: true,
+      })
+      .accounts({
+        gig: gigPda,
+        owner: freelancer.publicKey,
+      })
+      .signers([freelancer])
+      .rpc()
+
+    const gig = await program.account.gig.fetch(gigPda)
+    const deal = await program.account.deal.fetch(dealPda)
+
+    assert.ok(gig.asking.eq(asking))
+    assert.ok(gig.minCompletionTime.eq(minCompletionTime))
+    assert.ok(deal.offer.eq(offer))
   })
 
   it('should not allow the freelancer to close a gig with a pending deal', async () => {
@@ -232,7 +474,7 @@ describe('Gig & Deal interaction', () => {
     } catch (e) {
       const err = e as AnchorError
 
-      assert.ok(err.error.errorCode.code === 'ConstraintRaw')
+      assert.ok(err.error.errorCode.code === 'GigHasPendingDeals')
     }
   })
 
@@ -257,16 +499,47 @@ describe('Gig & Deal interaction', () => {
     } catch (e) {
       const err = e as AnchorError
 
-      assert.ok(err.error.errorCode.code === 'ConstraintRaw')
+      assert.ok(err.error.errorCode.code === 'InvalidDealTransition')
+    }
+  })
+
+  it('should not refund the client before the deadline', async () => {
+    try {
+      await program.methods
+        .claimExpired()
+        .accounts({
+          deal: dealPda,
+          gig: gigPda,
+          freelancerProfile: getProfilePda(freelancer.publicKey),
+          escrow: dealEscrowPda,
+          clientWallet: clientUsdc,
+          mint: usdc.token,
+          client: client.publicKey,
+          systemProgram: SystemProgram.programId,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        })
+        .signers([client])
+        .rpc()
+
+      assert.ok(false)
+    } catch (e) {
+      const err = e as AnchorError
+
+      assert.ok(err.error.errorCode.code === 'DeadlineNotReached')
     }
   })
 
   it('should allow the client to complete the deal', async () => {
+    const treasuryBalanceBefore =
+      await program.provider.connection.getTokenAccountBalance(treasuryUsdc)
+
     await program.methods
       .completeDeal()
       .accounts({
         client: client.publicKey,
         deal: dealPda,
+        receipt: receiptPda,
         escrow: dealEscrowPda,
         gig: gigPda,
         mint: usdc.token,
@@ -274,6 +547,13 @@ describe('Gig & Deal interaction', () => {
         referrer: null,
         referrerTokenAccount: null,
         config: configPda,
+        freelancerProfile: getProfilePda(freelancer.publicKey),
+        freelancerCredential: getCredentialPda(freelancer.publicKey),
+        freelancerCredentialAccount: getCredentialAccount(freelancer.publicKey),
+        clientProfile: getProfilePda(client.publicKey),
+        clientCredential: getCredentialPda(client.publicKey),
+        clientCredentialAccount: getCredentialAccount(client.publicKey),
+        credentialCollection: credentialCollectionPda,
         freelancer: freelancer.publicKey,
         freelancerTokenAccount: freelancerUsdc,
         treasury: treasury.publicKey,
@@ -281,15 +561,311 @@ describe('Gig & Deal interaction', () => {
         systemProgram: SystemProgram.programId,
         associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
         tokenProgram: TOKEN_PROGRAM_ID,
+        token2022Program: TOKEN_2022_PROGRAM_ID,
         rent: SYSVAR_RENT_PUBKEY,
       })
+      .preInstructions([computeBudgetIx])
       .signers([client])
       .rpc()
 
+    const deal = await program.account.deal.fetchNullable(dealPda)
+    const receipt = await program.account.receipt.fetch(receiptPda)
+
+    const dealEscrow = await program.provider.connection.getAccountInfo(
+      dealEscrowPda,
+    )
+
     const freelancerBalance =
       await program.provider.connection.getTokenAccountBalance(freelancerUsdc)
 
+    const treasuryBalance =
+      await program.provider.connection.getTokenAccountBalance(treasuryUsdc)
+
+    // A deal without milestones is paid out and closed at once
+    assert.ok(deal === null)
+    assert.ok(dealEscrow === null)
+    assert.ok(receipt.released.eq(offer))
     assert.ok(freelancerBalance.value.uiAmountString === '95')
+    // Freelancer fee of 5 and client fee of 3
+    assert.ok(
+      new BN(treasuryBalance.value.amount)
+        .sub(new BN(treasuryBalanceBefore.value.amount))
+        .eq(new BN(8_000_000)),
+    )
+
+    const freelancerProfile = await program.account.profile.fetch(
+      getProfilePda(freelancer.publicKey),
+    )
+    const clientProfile = await program.account.profile.fetch(
+      getProfilePda(client.publicKey),
+    )
+
+    assert.ok(freelancerProfile.completedDeals === 1)
+    assert.ok(freelancerProfile.onTimeDeliveries === 1)
+    assert.ok(freelancerProfile.volumes[0].mint.equals(usdc.token))
+    assert.ok(freelancerProfile.volumes[0].amount.eq(offer))
+    assert.ok(clientProfile.completedDeals === 1)
+    assert.ok(clientProfile.deliveries === 0)
+
+    const credentialBalance =
+      await program.provider.connection.getTokenAccountBalance(
+        getCredentialAccount(freelancer.publicKey),
+      )
+
+    assert.ok(credentialBalance.value.uiAmountString === '1')
+  })
+
+  it('should allow both parties to review the deal once', async () => {
+    const leaveReview = (author: Keypair, subject: PublicKey, score: number) =>
+      program.methods
+        .leaveReview({ score, content: Array(32).fill(score) })
+        .accounts({
+          receipt: receiptPda,
+          review: findProgramAddressSync(
+            [
+              Buffer.from('review'),
+              dealPda.toBytes(),
+              author.publicKey.toBytes(),
+            ],
+            program.programId,
+          )[0],
+          gig: gigPda,
+          subject,
+          subjectProfile: getProfilePda(subject),
+          subjectCredential: getCredentialPda(subject),
+          subjectCredentialAccount: getCredentialAccount(subject),
+          credentialCollection: credentialCollectionPda,
+          config: configPda,
+          author: author.publicKey,
+          systemProgram: SystemProgram.programId,
+          token2022Program: TOKEN_2022_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        })
+        .signers([author])
+        .rpc()
+
+    await leaveReview(client, freelancer.publicKey, 5)
+    await leaveReview(freelancer, client.publicKey, 4)
+
+    try {
+      await leaveReview(client, freelancer.publicKey, 1)
+
+      assert.ok(false)
+    } catch (e) {
+      assert.ok(!(e instanceof AnchorError))
+    }
+
+    const gig = await program.account.gig.fetch(gigPda)
+    const freelancerProfile = await program.account.profile.fetch(
+      getProfilePda(freelancer.publicKey),
+    )
+    const clientProfile = await program.account.profile.fetch(
+      getProfilePda(client.publicKey),
+    )
+
+    assert.ok(gig.ratingTotal.eqn(5))
+    assert.ok(gig.ratingCount === 1)
+    assert.ok(freelancerProfile.ratingTotal.eqn(5))
+    assert.ok(freelancerProfile.ratingCount === 1)
+    assert.ok(clientProfile.ratingTotal.eqn(4))
+    assert.ok(clientProfile.ratingCount === 1)
+  })
+
+  it('should not allow anyone else to review the deal', async () => {
+    try {
+      await program.methods
+        .leaveReview({ score: 1, content: Array(32).fill(0) })
+        .accounts({
+          receipt: receiptPda,
+          review: findProgramAddressSync(
+            [
+              Buffer.from('review'),
+              dealPda.toBytes(),
+              authority.publicKey.toBytes(),
+            ],
+            program.programId,
+          )[0],
+          gig: gigPda,
+          subject: freelancer.publicKey,
+          subjectProfile: getProfilePda(freelancer.publicKey),
+          subjectCredential: getCredentialPda(freelancer.publicKey),
+          subjectCredentialAccount: getCredentialAccount(
+            freelancer.publicKey,
+          ),
+          credentialCollection: credentialCollectionPda,
+          config: configPda,
+          author: authority.publicKey,
+          systemProgram: SystemProgram.programId,
+          token2022Program: TOKEN_2022_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        })
+        .signers([authority])
+        .rpc()
+
+      assert.ok(false)
+    } catch (e) {
+      const err = e as AnchorError
+
+      assert.ok(err.error.errorCode.code === 'Unauthorized')
+    }
+  })
+
+  it('should refund the client once the deadline passes without a delivery', async () => {
+    const expressGigId = Keypair.generate()
+    const [expressGigPda] = findProgramAddressSync(
+      [
+        Buffer.from('gig'),
+        freelancer.publicKey.toBytes(),
+        expressGigId.publicKey.toBytes().slice(0, 8),
+      ],
+      program.programId,
+    )
+    const expiredDealId = Keypair.generate()
+    const [expiredDealPda] = findProgramAddressSync(
+      [
+        Buffer.from('deal'),
+        client.publicKey.toBytes(),
+        expressGigPda.toBytes(),
+        expiredDealId.publicKey.toBytes().slice(0, 8),
+      ],
+      program.programId,
+    )
+    const expiredEscrowPda = await usdc.getAssociatedTokenAccount(
+      expiredDealPda,
+      true,
+    )
+    const shortDeadline = new BN(Math.floor(Date.now() / 1000) + 3)
+
+    await program.methods
+      .createGig({
+        asking: offer,
+        category: 0,
+        skills: new BN(0),
+        minCompletionTime: new BN(0),
+        isPublished: true,
+        acceptsReferrals: false,
+      })
+      .accounts({
+        id: expressGigId.publicKey,
+        config: configPda,
+        gig: expressGigPda,
+        mint: usdc.token,
+        owner: freelancer.publicKey,
+        payer: freelancer.publicKey,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([freelancer, expressGigId])
+      .rpc()
+
+    await program.methods
+      .createDeal({
+        offer,
+        deadline: shortDeadline,
+        milestones: [],
+      })
+      .accounts({
+        config: configPda,
+        mintConfig: mintConfigPda,
+        referrer: null,
+        deal: expiredDealPda,
+        receipt: getReceiptPda(expiredDealPda),
+        id: expiredDealId.publicKey,
+        gig: expressGigPda,
+        mint: usdc.token,
+        owner: client.publicKey,
+        ownerWallet: clientUsdc,
+        escrow: expiredEscrowPda,
+        systemProgram: SystemProgram.programId,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        rent: SYSVAR_RENT_PUBKEY,
+      })
+      .signers([client, expiredDealId])
+      .rpc()
+
+    await program.methods
+      .acceptDeal({ expectedOffer: offer, expectedDeadline: shortDeadline })
+      .accounts({
+        deal: expiredDealPda,
+        freelancer: freelancer.publicKey,
+        gig: expressGigPda,
+        config: configPda,
+      })
+      .signers([freelancer])
+      .rpc()
+
+    const clientBalanceBefore =
+      await program.provider.connection.getTokenAccountBalance(clientUsdc)
+    const freelancerProfileBefore = await program.account.profile.fetch(
+      getProfilePda(freelancer.publicKey),
+    )
+
+    await wait(5000)
+
+    // Too late to front-run the refund with a delivery
+    try {
+      await program.methods
+        .submitDelivery({ delivery: Array(32).fill(1) })
+        .accounts({
+          deal: expiredDealPda,
+          freelancer: freelancer.publicKey,
+        })
+        .signers([freelancer])
+        .rpc()
+
+      assert.ok(false)
+    } catch (e) {
+      const err = e as AnchorError
+
+      assert.ok(err.error.errorCode.code === 'DeadlinePassed')
+    }
+
+    await program.methods
+      .claimExpired()
+      .accounts({
+        deal: expiredDealPda,
+        gig: expressGigPda,
+        freelancerProfile: getProfilePda(freelancer.publicKey),
+        escrow: expiredEscrowPda,
+        clientWallet: clientUsdc,
+        mint: usdc.token,
+        client: client.publicKey,
+        systemProgram: SystemProgram.programId,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+      })
+      .signers([client])
+      .rpc()
+
+    const deal = await program.account.deal.fetchNullable(expiredDealPda)
+    const gig = await program.account.gig.fetch(expressGigPda)
+    const freelancerProfile = await program.account.profile.fetch(
+      getProfilePda(freelancer.publicKey),
+    )
+
+    const dealEscrow = await program.provider.connection.getAccountInfo(
+      expiredEscrowPda,
+    )
+
+    const clientBalance =
+      await program.provider.connection.getTokenAccountBalance(clientUsdc)
+
+    assert.ok(deal === null)
+    assert.ok(dealEscrow === null)
+    assert.ok(gig.pendingDeals === 0)
+    // The missed delivery counts against the freelancer
+    assert.ok(
+      freelancerProfile.deliveries === freelancerProfileBefore.deliveries + 1,
+    )
+    assert.ok(
+      freelancerProfile.onTimeDeliveries ===
+        freelancerProfileBefore.onTimeDeliveries,
+    )
+    // The offer and the client fee are refunded
+    assert.ok(
+      new BN(clientBalance.value.amount)
+        .sub(new BN(clientBalanceBefore.value.amount))
+        .eq(new BN(103_000_000)),
+    )
   })
 
   it('should allow the freelancer to close a gig', async () => {
@@ -0,0 +1,854 @@
+import { AnchorError } from '@coral-xyz/anchor'
+import { findProgramAddressSync } from '@coral-xyz/anchor/dist/cjs/utils/pubkey'
+import {
+  ASSOCIATED_TOKEN_PROGRAM_ID,
+  NATIVE_MINT,
+  TOKEN_2022_PROGRAM_ID,
+  TOKEN_PROGRAM_ID,
+} from '@solana/spl-token'
+import {
+  Keypair,
+  LAMPORTS_PER_SOL,
+  PublicKey,
+  SystemProgram,
+  SYSVAR_RENT_PUBKEY,
+} from '@solana/web3.js'
+import { BN } from 'bn.js'
+import { assert } from 'chai'
+import {
+  computeBudgetIx,
+  configPda,
+  credentialCollectionPda,
+  getCredentialAccount,
+  getCredentialPda,
+  getProfilePda,
+  getReceiptPda,
+  listMint,
+  program,
+  treasury,
+  wait,
+} from './common'
+
+let freelancer = Keypair.generate()
+let client = Keypair.generate()
+let returningClient = Keypair.generate()
+let gigId = Keypair.generate()
+let gigNonce = gigId.publicKey.toBytes().slice(0, 8)
+let [gigPda] = findProgramAddressSync(
+  [Buffer.from('gig'), freelancer.publicKey.toBytes(), gigNonce],
+  program.programId,
+)
+let mintConfigPda: PublicKey
+let dealId = Keypair.generate()
+let [dealPda] = findProgramAddressSync(
+  [
+    Buffer.from('deal'),
+    client.publicKey.toBytes(),
+    gigPda.toBytes(),
+    dealId.publicKey.toBytes().slice(0, 8),
+  ],
+  program.programId,
+)
+let [receiptPda] = findProgramAddressSync(
+  [Buffer.from('receipt'), dealPda.toBytes()],
+  program.programId,
+)
+
+// Same client and gig, the deal nonce keeps both deals open side by side
+let milestoneDealId = Keypair.generate()
+let [milestoneDealPda] = findProgramAddressSync(
+  [
+    Buffer.from('deal'),
+    returningClient.publicKey.toBytes(),
+    gigPda.toBytes(),
+    milestoneDealId.publicKey.toBytes().slice(0, 8),
+  ],
+  program.programId,
+)
+let [milestoneReceiptPda] = findProgramAddressSync(
+  [Buffer.from('receipt'), milestoneDealPda.toBytes()],
+  program.programId,
+)
+let silentDealId = Keypair.generate()
+let [silentDealPda] = findProgramAddressSync(
+  [
+    Buffer.from('deal'),
+    returningClient.publicKey.toBytes(),
+    gigPda.toBytes(),
+    silentDealId.publicKey.toBytes().slice(0, 8),
+  ],
+  program.programId,
+)
+let [silentReceiptPda] = findProgramAddressSync(
+  [Buffer.from('receipt'), silentDealPda.toBytes()],
+  program.programId,
+)
+
+let referrer = Keypair.generate()
+let [referrerPda] = findProgramAddressSync(
+  [Buffer.from('referrer'), referrer.publicKey.toBytes()],
+  program.programId,
+)
+let referredDealId = Keypair.generate()
+let [referredDealPda] = findProgramAddressSync(
+  [
+    Buffer.from('deal'),
+    returningClient.publicKey.toBytes(),
+    gigPda.toBytes(),
+    referredDealId.publicKey.toBytes().slice(0, 8),
+  ],
+  program.programId,
+)
+let [referredReceiptPda] = findProgramAddressSync(
+  [Buffer.from('receipt'), referredDealPda.toBytes()],
+  program.programId,
+)
+
+describe('Gig & Deal paid in SOL', () => {
+  const offer = new BN(LAMPORTS_PER_SOL)
+  const deadline = new BN(new Date().getTime() / 1000 + 60 * 24 * 2)
+
+  const createDeal = () =>
+    program.methods
+      .createDeal({
+        offer,
+        deadline,
+        milestones: [],
+      })
+      .accounts({
+        config: configPda,
+        mintConfig: mintConfigPda,
+        referrer: null,
+        deal: dealPda,
+        receipt: getReceiptPda(dealPda),
+        id: dealId.publicKey,
+        gig: gigPda,
+        mint: null,
+        owner: client.publicKey,
+        ownerWallet: null,
+        escrow: null,
+        systemProgram: SystemProgram.programId,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        rent: SYSVAR_RENT_PUBKEY,
+      })
+      .signers([client, dealId])
+      .rpc()
+
+  before(async () => {
+    const airdropAmount = 2 * LAMPORTS_PER_SOL
+
+    await program.provider.connection.requestAirdrop(
+      freelancer.publicKey,
+      airdropAmount,
+    )
+
+    await wait(500)
+
+    await program.provider.connection.requestAirdrop(
+      client.publicKey,
+      airdropAmount,
+    )
+
+    await wait(500)
+
+    await program.provider.connection.requestAirdrop(
+      returningClient.publicKey,
+      2 * airdropAmount,
+    )
+
+    await wait(500)
+
+    // SOL gigs are listed under the native mint
+    mintConfigPda = await listMint(NATIVE_MINT)
+  })
+
+  it('should allow the freelancer to create a gig paid in SOL', async () => {
+    await program.methods
+      .createGig({
+        asking: offer,
+        category: 0,
+        skills: new BN(0),
+        minCompletionTime: new BN(60 * 24),
+        isPublished: true,
+        acceptsReferrals: true,
+      })
+      .accounts({
+        id: gigId.publicKey,
+        config: configPda,
+        gig: gigPda,
+        mint: null,
+        owner: freelancer.publicKey,
+        payer: freelancer.publicKey,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([freelancer, gigId])
+      .rpc()
+
+    const gig = await program.account.gig.fetch(gigPda)
+
+    assert.ok(gig.mint === null)
+  })
+
+  it('should escrow the offer and client fee in the deal account', async () => {
+    await createDeal()
+
+    const dealInfo = await program.provider.connection.getAccountInfo(dealPda)
+    const rent =
+      await program.provider.connection.getMinimumBalanceForRentExemption(
+        dealInfo.data.length,
+      )
+
+    // 3% client fee on top of the offer
+    assert.ok(dealInfo.lamports - rent === 1_030_000_000)
+  })
+
+  it('should refund the client when the deal is closed', async () => {
+    await program.methods
+      .closeDeal()
+      .accounts({
+        client: client.publicKey,
+        deal: dealPda,
+        escrow: null,
+        gig: gigPda,
+        mint: null,
+        ownerWallet: null,
+        signer: client.publicKey,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([client])
+      .rpc()
+
+    const deal = await program.account.deal.fetchNullable(dealPda)
+    const clientBalance = await program.provider.connection.getBalance(
+      client.publicKey,
+    )
+
+    assert.ok(deal === null)
+    // Only the transaction fees were spent
+    assert.ok(clientBalance > 2 * LAMPORTS_PER_SOL - 100_000)
+  })
+
+  it('should allow the freelancer to reject a deal', async () => {
+    await createDeal()
+
+    const clientBalanceBefore = await program.provider.connection.getBalance(
+      client.publicKey,
+    )
+
+    await program.methods
+      .rejectDeal({ reason: 1 })
+      .accounts({
+        deal: dealPda,
+        gig: gigPda,
+        client: client.publicKey,
+        escrow: null,
+        clientWallet: null,
+        mint: null,
+        freelancer: freelancer.publicKey,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+      })
+      .signers([freelancer])
+      .rpc()
+
+    const deal = await program.account.deal.fetchNullable(dealPda)
+    const clientBalance = await program.provider.connection.getBalance(
+      client.publicKey,
+    )
+
+    assert.ok(deal === null)
+    // Offer and client fee are refunded on top of the deal's rent
+    assert.ok(clientBalance - clientBalanceBefore > 1_030_000_000)
+  })
+
+  it('should escrow the difference when the client accepts a counter offer', async () => {
+    await createDeal()
+
+    await program.methods
+      .counterOffer({
+        offer: new BN(1_500_000_000),
+        deadline,
+      })
+      .accounts({
+        gig: gigPda,
+        deal: dealPda,
+        freelancer: freelancer.publicKey,
+      })
+      .signers([freelancer])
+      .rpc()
+
+    const acceptCounter = (expectedOffer: BN) =>
+      program.methods
+        .acceptCounter({ expectedOffer, expectedDeadline: deadline })
+        .accounts({
+          deal: dealPda,
+          gig: gigPda,
+          escrow: null,
+          ownerWallet: null,
+          mint: null,
+          mintConfig: mintConfigPda,
+          client: client.publicKey,
+          systemProgram: SystemProgram.programId,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([client])
+        .rpc()
+
+    // The client only agrees to the counter offer it has seen
+    try {
+      await acceptCounter(offer)
+
+      assert.ok(false)
+    } catch (e) {
+      const err = e as AnchorError
+
+      assert.ok(err.error.errorCode.code === 'TermsMismatch')
+    }
+
+    await acceptCounter(new BN(1_500_000_000))
+
+    const deal = await program.account.deal.fetch(dealPda)
+    const dealInfo = await program.provider.connection.getAccountInfo(dealPda)
+    const rent =
+      await program.provider.connection.getMinimumBalanceForRentExemption(
+        dealInfo.data.length,
+      )
+
+    assert.ok(deal.offer.eq(new BN(1_500_000_000)))
+    assert.ok(deal.counterOffer === null)
+    assert.ok(dealInfo.lamports - rent === 1_545_000_000)
+
+    await program.methods
+      .closeDeal()
+      .accounts({
+        client: client.publicKey,
+        deal: dealPda,
+        escrow: null,
+        gig: gigPda,
+        mint: null,
+        ownerWallet: null,
+        signer: client.publicKey,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([client])
+      .rpc()
+  })
+
+  it('should pay the freelancer in SOL when the deal is completed', async () => {
+    await createDeal()
+
+    await program.methods
+      .acceptDeal({ expectedOffer: offer, expectedDeadline: deadline })
+      .accounts({
+        deal: dealPda,
+        freelancer: freelancer.publicKey,
+        gig: gigPda,
+        config: configPda,
+      })
+      .signers([freelancer])
+      .rpc()
+
+    const freelancerBalanceBefore = await program.provider.connection.getBalance(
+      freelancer.publicKey,
+    )
+
+    let completed: any
+    const listener = program.addEventListener('DealCompleted', (event) => {
+      completed = event
+    })
+
+    await program.methods
+      .completeDeal()
+      .accounts({
+        client: client.publicKey,
+        deal: dealPda,
+        receipt: receiptPda,
+        escrow: null,
+        gig: gigPda,
+        mint: null,
+        signer: client.publicKey,
+        referrer: null,
+        referrerTokenAccount: null,
+        config: configPda,
+        freelancerProfile: getProfilePda(freelancer.publicKey),
+        freelancerCredential: getCredentialPda(freelancer.publicKey),
+        freelancerCredentialAccount: getCredentialAccount(freelancer.publicKey),
+        clientProfile: getProfilePda(client.publicKey),
+        clientCredential: getCredentialPda(client.publicKey),
+        clientCredentialAccount: getCredentialAccount(client.publicKey),
+        credentialCollection: credentialCollectionPda,
+        freelancer: freelancer.publicKey,
+        freelancerTokenAccount: null,
+        treasury: treasury.publicKey,
+        treasuryTokenAccount: null,
+        systemProgram: SystemProgram.programId,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        token2022Program: TOKEN_2022_PROGRAM_ID,
+        rent: SYSVAR_RENT_PUBKEY,
+      })
+      .preInstructions([computeBudgetIx])
+      .signers([client])
+      .rpc()
+
+    const freelancerBalance = await program.provider.connection.getBalance(
+      freelancer.publicKey,
+    )
+
+    // 5% freelancer fee
+    assert.ok(freelancerBalance - freelancerBalanceBefore === 950_000_000)
+
+    await wait(500)
+    await program.removeEventListener(listener)
+
+    assert.ok(completed.freelancerPay.eq(new BN(950_000_000)))
+    // 5% freelancer fee and 3% client fee
+    assert.ok(completed.treasuryPay.eq(new BN(80_000_000)))
+    assert.ok(completed.clientFee.eq(new BN(30_000_000)))
+  })
+
+  it('should not reuse the address of a completed deal', async () => {
+    try {
+      await createDeal()
+
+      assert.ok(false)
+    } catch (e) {
+      const err = e as AnchorError
+
+      assert.ok(err.error.errorCode.code === 'DealAddressUsed')
+    }
+  })
+
+  it('should not allow milestones due in the past', async () => {
+    try {
+      await program.methods
+        .createDeal({
+          offer,
+          deadline,
+          milestones: [
+            { amount: new BN(400_000_000), dueDate: new BN(1) },
+            { amount: new BN(600_000_000), dueDate: deadline },
+          ],
+        })
+        .accounts({
+          config: configPda,
+          mintConfig: mintConfigPda,
+          referrer: null,
+          deal: milestoneDealPda,
+          receipt: getReceiptPda(milestoneDealPda),
+          id: milestoneDealId.publicKey,
+          gig: gigPda,
+          mint: null,
+          owner: returningClient.publicKey,
+          ownerWallet: null,
+          escrow: null,
+          systemProgram: SystemProgram.programId,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          rent: SYSVAR_RENT_PUBKEY,
+        })
+        .signers([returningClient, milestoneDealId])
+        .rpc()
+
+      assert.ok(false)
+    } catch (e) {
+      const err = e as AnchorError
+
+      assert.ok(err.error.errorCode.code === 'InvalidMilestones')
+    }
+  })
+
+  it('should pay the freelancer milestone by milestone', async () => {
+    await program.methods
+      .createDeal({
+        offer,
+        deadline,
+        milestones: [
+          { amount: new BN(400_000_000), dueDate: deadline.subn(60 * 12) },
+          { amount: new BN(600_000_000), dueDate: deadline },
+        ],
+      })
+      .accounts({
+        config: configPda,
+        mintConfig: mintConfigPda,
+        referrer: null,
+        deal: milestoneDealPda,
+        receipt: getReceiptPda(milestoneDealPda),
+        id: milestoneDealId.publicKey,
+        gig: gigPda,
+        mint: null,
+        owner: returningClient.publicKey,
+        ownerWallet: null,
+        escrow: null,
+        systemProgram: SystemProgram.programId,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        rent: SYSVAR_RENT_PUBKEY,
+      })
+      .signers([returningClient, milestoneDealId])
+      .rpc()
+
+    // Milestones are tied to the offer, it cannot be countered
+    try {
+      await program.methods
+        .counterOffer({
+          offer: new BN(1_500_000_000),
+          deadline,
+        })
+        .accounts({
+          gig: gigPda,
+          deal: milestoneDealPda,
+          freelancer: freelancer.publicKey,
+        })
+        .signers([freelancer])
+        .rpc()
+
+      assert.ok(false)
+    } catch (e) {
+      const err = e as AnchorError
+
+      assert.ok(err.error.errorCode.code === 'InvalidMilestones')
+    }
+
+    await program.methods
+      .acceptDeal({ expectedOffer: offer, expectedDeadline: deadline })
+      .accounts({
+        deal: milestoneDealPda,
+        freelancer: freelancer.publicKey,
+        gig: gigPda,
+        config: configPda,
+      })
+      .signers([freelancer])
+      .rpc()
+
+    const milestoneAccounts = {
+      client: returningClient.publicKey,
+      deal: milestoneDealPda,
+      receipt: milestoneReceiptPda,
+      escrow: null,
+      gig: gigPda,
+      mint: null,
+      signer: returningClient.publicKey,
+      referrer: null,
+      referrerTokenAccount: null,
+      config: configPda,
+      freelancerProfile: getProfilePda(freelancer.publicKey),
+      freelancerCredential: getCredentialPda(freelancer.publicKey),
+      freelancerCredentialAccount: getCredentialAccount(freelancer.publicKey),
+      clientProfile: getProfilePda(returningClient.publicKey),
+      clientCredential: getCredentialPda(returningClient.publicKey),
+      clientCredentialAccount: getCredentialAccount(returningClient.publicKey),
+      credentialCollection: credentialCollectionPda,
+      freelancer: freelancer.publicKey,
+      freelancerTokenAccount: null,
+      treasury: treasury.publicKey,
+      treasuryTokenAccount: null,
+      systemProgram: SystemProgram.programId,
+      associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+      tokenProgram: TOKEN_PROGRAM_ID,
+      token2022Program: TOKEN_2022_PROGRAM_ID,
+      rent: SYSVAR_RENT_PUBKEY,
+    }
+
+    const releaseMilestone = (index: number) =>
+      program.methods
+        .releaseMilestone({ index })
+        .accounts(milestoneAccounts)
+        .preInstructions([computeBudgetIx])
+        .signers([returningClient])
+        .rpc()
+
+    await program.methods
+      .submitDelivery({ delivery: Array(32).fill(1) })
+      .accounts({
+        deal: milestoneDealPda,
+        freelancer: freelancer.publicKey,
+      })
+      .signers([freelancer])
+      .rpc()
+
+    const freelancerBalanceBefore = await program.provider.connection.getBalance(
+      freelancer.publicKey,
+    )
+
+    await releaseMilestone(0)
+
+    let deal = await program.account.deal.fetchNullable(milestoneDealPda)
+    const freelancerBalance = await program.provider.connection.getBalance(
+      freelancer.publicKey,
+    )
+
+    // 5% freelancer fee on the first milestone only
+    assert.ok(freelancerBalance - freelancerBalanceBefore === 380_000_000)
+    assert.ok('accepted' in deal.state)
+    assert.ok(deal.timeDelivered.eqn(0))
+
+    // The delivery of the first milestone does not cover the second one
+    await wait(6000)
+
+    try {
+      await program.methods
+        .autoComplete()
+        .accounts({ ...milestoneAccounts, signer: freelancer.publicKey })
+        .preInstructions([computeBudgetIx])
+        .signers([freelancer])
+        .rpc()
+
+      assert.ok(false)
+    } catch (e) {
+      const err = e as AnchorError
+
+      assert.ok(err.error.errorCode.code === 'NoDelivery')
+    }
+
+    await releaseMilestone(1)
+
+    deal = await program.account.deal.fetchNullable(milestoneDealPda)
+    const receipt = await program.account.receipt.fetch(milestoneReceiptPda)
+
+    assert.ok(deal === null)
+    assert.ok(receipt.released.eq(offer))
+  })
+
+  it('should pay the freelancer once the review window passes', async () => {
+    await program.methods
+      .createDeal({
+        offer,
+        deadline,
+        milestones: [],
+      })
+      .accounts({
+        config: configPda,
+        mintConfig: mintConfigPda,
+        referrer: null,
+        deal: silentDealPda,
+        receipt: getReceiptPda(silentDealPda),
+        id: silentDealId.publicKey,
+        gig: gigPda,
+        mint: null,
+        owner: returningClient.publicKey,
+        ownerWallet: null,
+        escrow: null,
+        systemProgram: SystemProgram.programId,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        rent: SYSVAR_RENT_PUBKEY,
+      })
+      .signers([returningClient, silentDealId])
+      .rpc()
+
+    await program.methods
+      .acceptDeal({ expectedOffer: offer, expectedDeadline: deadline })
+      .accounts({
+        deal: silentDealPda,
+        freelancer: freelancer.publicKey,
+        gig: gigPda,
+        config: configPda,
+      })
+      .signers([freelancer])
+      .rpc()
+
+    await program.methods
+      .submitDelivery({ delivery: Array(32).fill(1) })
+      .accounts({
+        deal: silentDealPda,
+        freelancer: freelancer.publicKey,
+      })
+      .signers([freelancer])
+      .rpc()
+
+    try {
+      await program.methods
+        .claimExpired()
+        .accounts({
+          deal: silentDealPda,
+          gig: gigPda,
+          freelancerProfile: getProfilePda(freelancer.publicKey),
+          escrow: null,
+          clientWallet: null,
+          mint: null,
+          client: returningClient.publicKey,
+          systemProgram: SystemProgram.programId,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        })
+        .signers([returningClient])
+        .rpc()
+
+      assert.ok(false)
+    } catch (e) {
+      const err = e as AnchorError
+
+      assert.ok(err.error.errorCode.code === 'DealDelivered')
+    }
+
+    // Review window is a few seconds on localnet
+    await wait(6000)
+
+    const freelancerBalanceBefore = await program.provider.connection.getBalance(
+      freelancer.publicKey,
+    )
+
+    // Cranked by the freelancer, the client stays silent
+    await program.methods
+      .autoComplete()
+      .accounts({
+        client: returningClient.publicKey,
+        deal: silentDealPda,
+        receipt: silentReceiptPda,
+        escrow: null,
+        gig: gigPda,
+        mint: null,
+        signer: freelancer.publicKey,
+        referrer: null,
+        referrerTokenAccount: null,
+        config: configPda,
+        freelancerProfile: getProfilePda(freelancer.publicKey),
+        freelancerCredential: getCredentialPda(freelancer.publicKey),
+        freelancerCredentialAccount: getCredentialAccount(freelancer.publicKey),
+        clientProfile: getProfilePda(returningClient.publicKey),
+        clientCredential: getCredentialPda(returningClient.publicKey),
+        clientCredentialAccount: getCredentialAccount(
+          returningClient.publicKey,
+        ),
+        credentialCollection: credentialCollectionPda,
+        freelancer: freelancer.publicKey,
+        freelancerTokenAccount: null,
+        treasury: treasury.publicKey,
+        treasuryTokenAccount: null,
+        systemProgram: SystemProgram.programId,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        token2022Program: TOKEN_2022_PROGRAM_ID,
+        rent: SYSVAR_RENT_PUBKEY,
+      })
+      .preInstructions([computeBudgetIx])
+      .signers([freelancer])
+      .rpc()
+
+    const deal = await program.account.deal.fetchNullable(silentDealPda)
+    const freelancerBalance = await program.provider.connection.getBalance(
+      freelancer.publicKey,
+    )
+
+    assert.ok(deal === null)
+    // 5% freelancer fee, minus the transaction fee paid by the freelancer
+    assert.ok(freelancerBalance - freelancerBalanceBefore > 949_000_000)
+  })
+
+  it('should not complete a referred deal without paying the referrer', async () => {
+    const referredOffer = new BN(LAMPORTS_PER_SOL / 10)
+
+    await program.provider.connection.requestAirdrop(
+      referrer.publicKey,
+      LAMPORTS_PER_SOL,
+    )
+
+    await wait(500)
+
+    await program.methods
+      .registerReferrer()
+      .accounts({
+        referrer: referrerPda,
+        owner: referrer.publicKey,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([referrer])
+      .rpc()
+
+    await program.methods
+      .createDeal({
+        offer: referredOffer,
+        deadline,
+        milestones: [],
+      })
+      .accounts({
+        config: configPda,
+        mintConfig: mintConfigPda,
+        referrer: referrerPda,
+        deal: referredDealPda,
+        receipt: getReceiptPda(referredDealPda),
+        id: referredDealId.publicKey,
+        gig: gigPda,
+        mint: null,
+        owner: returningClient.publicKey,
+        ownerWallet: null,
+        escrow: null,
+        systemProgram: SystemProgram.programId,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        rent: SYSVAR_RENT_PUBKEY,
+      })
+      .signers([returningClient, referredDealId])
+      .rpc()
+
+    await program.methods
+      .acceptDeal({ expectedOffer: referredOffer, expectedDeadline: deadline })
+      .accounts({
+        deal: referredDealPda,
+        freelancer: freelancer.publicKey,
+        gig: gigPda,
+        config: configPda,
+      })
+      .signers([freelancer])
+      .rpc()
+
+    const completeDeal = (referrerWallet: PublicKey | null) =>
+      program.methods
+        .completeDeal()
+        .accounts({
+          client: returningClient.publicKey,
+          deal: referredDealPda,
+          receipt: referredReceiptPda,
+          escrow: null,
+          gig: gigPda,
+          mint: null,
+          signer: returningClient.publicKey,
+          referrer: referrerWallet,
+          referrerTokenAccount: null,
+          config: configPda,
+          freelancerProfile: getProfilePda(freelancer.publicKey),
+          freelancerCredential: getCredentialPda(freelancer.publicKey),
+          freelancerCredentialAccount: getCredentialAccount(
+            freelancer.publicKey,
+          ),
+          clientProfile: getProfilePda(returningClient.publicKey),
+          clientCredential: getCredentialPda(returningClient.publicKey),
+          clientCredentialAccount: getCredentialAccount(
+            returningClient.publicKey,
+          ),
+          credentialCollection: credentialCollectionPda,
+          freelancer: freelancer.publicKey,
+          freelancerTokenAccount: null,
+          treasury: treasury.publicKey,
+          treasuryTokenAccount: null,
+          systemProgram: SystemProgram.programId,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          token2022Program: TOKEN_2022_PROGRAM_ID,
+          rent: SYSVAR_RENT_PUBKEY,
+        })
+        .preInstructions([computeBudgetIx])
+        .signers([returningClient])
+        .rpc()
+
+    try {
+      await completeDeal(null)
+
+      assert.ok(false)
+    } catch (e) {
+      const err = e as AnchorError
+
+      assert.ok(err.error.errorCode.code === 'ReferrerMismatch')
+    }
+
+    const referrerBalanceBefore = await program.provider.connection.getBalance(
+      referrer.publicKey,
+    )
+
+    await completeDeal(referrer.publicKey)
+
+    const referrerBalance = await program.provider.connection.getBalance(
+      referrer.publicKey,
+    )
+
+    // 5% referral fee
+    assert.ok(referrerBalance - referrerBalanceBefore === 5_000_000)
+  })
+})
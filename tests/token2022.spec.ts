@@ -0,0 +1,290 @@
+import { findProgramAddressSync } from '@coral-xyz/anchor/dist/cjs/utils/pubkey'
+import {
+  ASSOCIATED_TOKEN_PROGRAM_ID,
+  createInitializeMintInstruction,
+  createInitializeTransferFeeConfigInstruction,
+  ExtensionType,
+  getAssociatedTokenAddressSync,
+  getMint,
+  getMintLen,
+  getOrCreateAssociatedTokenAccount,
+  getTransferFeeConfig,
+  mintTo,
+  TOKEN_2022_PROGRAM_ID,
+} from '@solana/spl-token'
+import {
+  Keypair,
+  LAMPORTS_PER_SOL,
+  PublicKey,
+  sendAndConfirmTransaction,
+  SystemProgram,
+  SYSVAR_RENT_PUBKEY,
+  Transaction,
+} from '@solana/web3.js'
+import { BN } from 'bn.js'
+import { assert } from 'chai'
+import {
+  authority,
+  computeBudgetIx,
+  configPda,
+  credentialCollectionPda,
+  getCredentialAccount,
+  getCredentialPda,
+  getProfilePda,
+  getReceiptPda,
+  listMint,
+  program,
+  treasury,
+  wait,
+} from './common'
+
+let freelancer = Keypair.generate()
+let client = Keypair.generate()
+let mintKp = Keypair.generate()
+let gigId = Keypair.generate()
+let [gigPda] = findProgramAddressSync(
+  [
+    Buffer.from('gig'),
+    freelancer.publicKey.toBytes(),
+    gigId.publicKey.toBytes().slice(0, 8),
+  ],
+  program.programId,
+)
+let dealId = Keypair.generate()
+let [dealPda] = findProgramAddressSync(
+  [
+    Buffer.from('deal'),
+    client.publicKey.toBytes(),
+    gigPda.toBytes(),
+    dealId.publicKey.toBytes().slice(0, 8),
+  ],
+  program.programId,
+)
+let [receiptPda] = findProgramAddressSync(
+  [Buffer.from('receipt'), dealPda.toBytes()],
+  program.programId,
+)
+let dealEscrowPda = getAssociatedTokenAddressSync(
+  mintKp.publicKey,
+  dealPda,
+  true,
+  TOKEN_2022_PROGRAM_ID,
+)
+let mintConfigPda: PublicKey
+let clientWallet: PublicKey
+let freelancerWallet: PublicKey
+let treasuryWallet: PublicKey
+
+describe('Gig & Deal paid in a Token-2022 mint with a transfer fee', () => {
+  const offer = new BN(100_000_000)
+  const deadline = new BN(new Date().getTime() / 1000 + 60 * 24 * 2)
+
+  before(async () => {
+    const connection = program.provider.connection
+
+    await connection.requestAirdrop(freelancer.publicKey, LAMPORTS_PER_SOL)
+
+    await wait(500)
+
+    await connection.requestAirdrop(client.publicKey, LAMPORTS_PER_SOL)
+
+    await wait(500)
+
+    // 1% transfer fee, 6 decimal places like USDC
+    const mintLen = getMintLen([ExtensionType.TransferFeeConfig])
+
+    await sendAndConfirmTransaction(
+      connection,
+      new Transaction().add(
+        SystemProgram.createAccount({
+          fromPubkey: authority.publicKey,
+          newAccountPubkey: mintKp.publicKey,
+          space: mintLen,
+          lamports: await connection.getMinimumBalanceForRentExemption(mintLen),
+          programId: TOKEN_2022_PROGRAM_ID,
+        }),
+        createInitializeTransferFeeConfigInstruction(
+          mintKp.publicKey,
+          authority.publicKey,
+          authority.publicKey,
+          1_00,
+          BigInt(1_000_000_000),
+          TOKEN_2022_PROGRAM_ID,
+        ),
+        createInitializeMintInstruction(
+          mintKp.publicKey,
+          6,
+          authority.publicKey,
+          null,
+          TOKEN_2022_PROGRAM_ID,
+        ),
+      ),
+      [authority, mintKp],
+    )
+
+    const getWallet = async (owner: PublicKey) => {
+      const account = await getOrCreateAssociatedTokenAccount(
+        connection,
+        authority,
+        mintKp.publicKey,
+        owner,
+        false,
+        undefined,
+        undefined,
+        TOKEN_2022_PROGRAM_ID,
+      )
+
+      return account.address
+    }
+
+    clientWallet = await getWallet(client.publicKey)
+    freelancerWallet = await getWallet(freelancer.publicKey)
+    treasuryWallet = await getWallet(treasury.publicKey)
+
+    await mintTo(
+      connection,
+      authority,
+      mintKp.publicKey,
+      clientWallet,
+      authority,
+      1_000_000_000,
+      [],
+      undefined,
+      TOKEN_2022_PROGRAM_ID,
+    )
+
+    mintConfigPda = await listMint(mintKp.publicKey, 2_000_000)
+
+    await program.methods
+      .createGig({
+        asking: offer,
+        category: 0,
+        skills: new BN(0),
+        minCompletionTime: new BN(60 * 24),
+        isPublished: true,
+        acceptsReferrals: true,
+      })
+      .accounts({
+        id: gigId.publicKey,
+        config: configPda,
+        gig: gigPda,
+        mint: mintKp.publicKey,
+        owner: freelancer.publicKey,
+        payer: freelancer.publicKey,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([freelancer, gigId])
+      .rpc()
+  })
+
+  it('should escrow the whole offer and fee despite the transfer fee', async () => {
+    await program.methods
+      .createDeal({
+        offer,
+        deadline,
+        milestones: [],
+      })
+      .accounts({
+        config: configPda,
+        mintConfig: mintConfigPda,
+        referrer: null,
+        deal: dealPda,
+        receipt: getReceiptPda(dealPda),
+        id: dealId.publicKey,
+        gig: gigPda,
+        mint: mintKp.publicKey,
+        owner: client.publicKey,
+        ownerWallet: clientWallet,
+        escrow: dealEscrowPda,
+        systemProgram: SystemProgram.programId,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+        rent: SYSVAR_RENT_PUBKEY,
+      })
+      .signers([client, dealId])
+      .rpc()
+
+    const escrowBalance =
+      await program.provider.connection.getTokenAccountBalance(dealEscrowPda)
+
+    // Offer and the 3% client fee, the client paid the transfer fee on top
+    assert.ok(escrowBalance.value.amount === '103000000')
+  })
+
+  it('should report the fee withheld from each payout', async () => {
+    await program.methods
+      .acceptDeal({ expectedOffer: offer, expectedDeadline: deadline })
+      .accounts({
+        deal: dealPda,
+        freelancer: freelancer.publicKey,
+        gig: gigPda,
+        config: configPda,
+      })
+      .signers([freelancer])
+      .rpc()
+
+    let completed = null
+
+    const listener = program.addEventListener('DealCompleted', (event) => {
+      completed = event
+    })
+
+    await program.methods
+      .completeDeal()
+      .accounts({
+        client: client.publicKey,
+        deal: dealPda,
+        receipt: receiptPda,
+        escrow: dealEscrowPda,
+        gig: gigPda,
+        mint: mintKp.publicKey,
+        signer: client.publicKey,
+        referrer: null,
+        referrerTokenAccount: null,
+        config: configPda,
+        freelancerProfile: getProfilePda(freelancer.publicKey),
+        freelancerCredential: getCredentialPda(freelancer.publicKey),
+        freelancerCredentialAccount: getCredentialAccount(freelancer.publicKey),
+        clientProfile: getProfilePda(client.publicKey),
+        clientCredential: getCredentialPda(client.publicKey),
+        clientCredentialAccount: getCredentialAccount(client.publicKey),
+        credentialCollection: credentialCollectionPda,
+        freelancer: freelancer.publicKey,
+        freelancerTokenAccount: freelancerWallet,
+        treasury: treasury.publicKey,
+        treasuryTokenAccount: treasuryWallet,
+        systemProgram: SystemProgram.programId,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+        token2022Program: TOKEN_2022_PROGRAM_ID,
+        rent: SYSVAR_RENT_PUBKEY,
+      })
+      .preInstructions([computeBudgetIx])
+      .signers([client])
+      .rpc()
+
+    await wait(500)
+    await program.removeEventListener(listener)
+
+    const freelancerBalance =
+      await program.provider.connection.getTokenAccountBalance(freelancerWallet)
+
+    assert.ok(completed.freelancerPay.eq(new BN(95_000_000)))
+    // 1% of the freelancer's and the treasury's payouts
+    assert.ok(completed.withheldFee.eq(new BN(1_030_000)))
+    assert.ok(freelancerBalance.value.amount === '94050000')
+
+    // The fee withheld on the deposit is harvested to the mint so the escrow can be closed.
+    const escrow = await program.provider.connection.getAccountInfo(
+      dealEscrowPda,
+    )
+    const mint = await getMint(
+      program.provider.connection,
+      mintKp.publicKey,
+      undefined,
+      TOKEN_2022_PROGRAM_ID,
+    )
+
+    assert.ok(escrow === null)
+    assert.ok(getTransferFeeConfig(mint).withheldAmount > BigInt(0))
+  })
+})